@@ -0,0 +1,118 @@
+use game::{
+    game::{Game, Turn, WinCondition},
+    player::{PlayerId, Position},
+};
+
+/// How far a player is from winning. Lower scores are better.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Score {
+    tier: Tier,
+    remaining_items: usize,
+    distance: usize,
+}
+
+/// Placed players are out of the game, so they are ranked apart from those
+/// still playing, however close these are to their goal.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Tier {
+    Placed { placement: usize },
+    Playing,
+    Lost,
+}
+
+impl Score {
+    pub const WIN: Score = Score::placed(1);
+
+    pub const LOSS: Score = Score {
+        tier: Tier::Lost,
+        remaining_items: 0,
+        distance: 0,
+    };
+
+    const fn placed(placement: usize) -> Self {
+        Score {
+            tier: Tier::Placed { placement },
+            remaining_items: 0,
+            distance: 0,
+        }
+    }
+
+    /// Whether the player has a placement, which no later turn can change.
+    pub fn is_placed(&self) -> bool {
+        matches!(self.tier, Tier::Placed { .. })
+    }
+}
+
+/// Scores the game from the point of view of the given player by the number of
+/// items left to collect and the distance to the next goal. In team mode, the
+/// items of the whole team count and the closest next item of a teammate is
+/// the goal. Placed players score by their placement, which beats not being
+/// placed at all.
+pub fn evaluate(game: &Game, player_id: PlayerId) -> Score {
+    if let Some(standing) = game.get_standing(player_id) {
+        return Score::placed(standing.get_placement());
+    }
+    if game.is_over() {
+        return Score::LOSS;
+    }
+
+    let players = game.get_players();
+    let Some(player) = players.get(player_id) else {
+        return Score::LOSS;
+    };
+
+    let board = game.get_board();
+    let next_items: Vec<_> = players
+        .teammates(player_id)
+        .filter_map(|p| p.get_next_to_collect())
+        .collect();
+    let goals: Vec<_> = if next_items.is_empty() {
+        match game.get_win_condition() {
+            WinCondition::ReturnToStart => vec![player.get_start_position()],
+            WinCondition::ReachCenter => vec![board.get_center()],
+            // There is nothing left to walk to.
+            WinCondition::CollectItems { .. } | WinCondition::MostItems { .. } => {
+                vec![player.get_position()]
+            }
+        }
+    } else {
+        next_items
+            .into_iter()
+            .filter_map(|item| board.find_item(item))
+            .collect()
+    };
+
+    let distance = goals
+        .into_iter()
+        .map(|goal| manhattan_distance(player.get_position(), goal))
+        .min()
+        // The items are on the free tile, so they cannot be reached this turn.
+        .unwrap_or_else(|| board.get_width().max(board.get_height()));
+
+    Score {
+        tier: Tier::Playing,
+        remaining_items: players
+            .teammates(player_id)
+            .map(|p| p.get_to_collect().len())
+            .sum(),
+        distance,
+    }
+}
+
+/// Plays the turn for the current player on a copy of the game.
+pub fn simulate(game: &Game, turn: Turn) -> Option<Game> {
+    let player_id = game.current_player_id();
+    let mut game = game.clone();
+
+    if !game.rotate_free_tile(turn.get_rotation()) {
+        return None;
+    }
+    game.shift_tiles(player_id, turn.get_side_index()).ok()?;
+    game.move_player(player_id, turn.get_destination()).ok()?;
+
+    Some(game)
+}
+
+fn manhattan_distance(a: Position, b: Position) -> usize {
+    a.get_x().abs_diff(b.get_x()) + a.get_y().abs_diff(b.get_y())
+}
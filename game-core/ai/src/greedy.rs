@@ -0,0 +1,21 @@
+use game::game::{Game, Turn};
+
+use crate::{
+    evaluation::{evaluate, simulate},
+    Strategy,
+};
+
+/// Picks the turn that brings the current player closest to its next item, or
+/// back to its start once every item is collected.
+pub struct GreedyBot;
+
+impl Strategy for GreedyBot {
+    fn choose_turn(&mut self, game: &Game) -> Option<Turn> {
+        let player_id = game.current_player_id();
+
+        game.iter_legal_turns()
+            .filter_map(|turn| simulate(game, turn).map(|after| (turn, after)))
+            .min_by_key(|(_, after)| evaluate(after, player_id))
+            .map(|(turn, _)| turn)
+    }
+}
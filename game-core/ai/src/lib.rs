@@ -0,0 +1,118 @@
+pub mod evaluation;
+pub mod greedy;
+pub mod lookahead;
+pub mod random;
+
+use game::game::{Game, Turn};
+use ts_interop::ts_interop;
+
+use crate::{greedy::GreedyBot, lookahead::LookaheadBot, random::RandomBot};
+
+/// Decides the turn of a computer player.
+pub trait Strategy {
+    /// Chooses a turn for the current player of the game, or `None` if there
+    /// is no legal turn (e.g. the game is over or the tiles were already
+    /// shifted this turn).
+    fn choose_turn(&mut self, game: &Game) -> Option<Turn>;
+}
+
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+    Random,
+    Greedy,
+    Lookahead,
+}
+
+impl StrategyKind {
+    pub fn create(self) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Random => Box::new(RandomBot::new()),
+            StrategyKind::Greedy => Box::new(GreedyBot),
+            StrategyKind::Lookahead => Box::new(LookaheadBot::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use game::{
+        game::{Game, GameStartSettings},
+        notation::parse_game,
+    };
+
+    use crate::{
+        evaluation::{evaluate, Score},
+        greedy::GreedyBot,
+        lookahead::LookaheadBot,
+        random::RandomBot,
+        Strategy, StrategyKind,
+    };
+
+    fn new_game() -> Game {
+        Game::new(GameStartSettings::new([0, 1].into(), 7, 3).with_seed(7)).unwrap()
+    }
+
+    fn play_turns(strategy: &mut dyn Strategy, turns: usize) -> Game {
+        let mut game = new_game();
+        for _ in 0..turns {
+            if game.is_over() {
+                break;
+            }
+            let player_id = game.current_player_id();
+            let turn = strategy.choose_turn(&game).unwrap();
+            assert!(game.rotate_free_tile(turn.get_rotation()));
+            assert!(game.shift_tiles(player_id, turn.get_side_index()).is_ok());
+            assert!(game.move_player(player_id, turn.get_destination()).is_ok());
+        }
+        game
+    }
+
+    fn collected(game: &Game) -> usize {
+        game.get_players()
+            .iter()
+            .map(|player| player.get_collected().len())
+            .sum()
+    }
+
+    #[test]
+    fn random_bot_plays_legal_turns() {
+        play_turns(&mut RandomBot::with_seed(1), 20);
+    }
+
+    #[test]
+    fn greedy_bot_collects_items() {
+        let game = play_turns(&mut GreedyBot, 40);
+        assert!(game.get_winner().is_some() || collected(&game) > 0);
+    }
+
+    #[test]
+    fn lookahead_bot_plays_legal_turns() {
+        play_turns(&mut LookaheadBot::default(), 6);
+    }
+
+    #[test]
+    fn no_turn_after_shift() {
+        let mut game = new_game();
+        let turn = GreedyBot.choose_turn(&game).unwrap();
+        assert!(game.shift_tiles(0, turn.get_side_index()).is_ok());
+        for kind in [
+            StrategyKind::Random,
+            StrategyKind::Greedy,
+            StrategyKind::Lookahead,
+        ] {
+            assert!(kind.create().choose_turn(&game).is_none());
+        }
+    }
+
+    #[test]
+    fn only_placed_players_win() {
+        // Player 0 has nothing left to collect, but the rounds are not over.
+        let notation = "L1T01L2/I0I0I0/L0T2L3 I1 0:1,0:0,0::/1:2,2:2,2::1 0 S - rounds5";
+        let game = parse_game(notation).unwrap();
+        let score = evaluate(&game, 0);
+        assert!(!score.is_placed());
+        assert!(score > Score::WIN);
+        assert!(score < Score::LOSS);
+    }
+}
@@ -0,0 +1,73 @@
+use game::{
+    game::{Game, Turn},
+    tile::Rotation,
+};
+
+use crate::{
+    evaluation::{evaluate, simulate},
+    Strategy,
+};
+
+/// Like [`GreedyBot`](crate::greedy::GreedyBot), but also considers how the
+/// next player can disturb the position by shifting tiles, and prefers turns
+/// that hold up against the worst of these shifts.
+pub struct LookaheadBot {
+    candidates: usize,
+}
+
+impl LookaheadBot {
+    /// Only the given number of best greedy turns are examined further.
+    pub fn new(candidates: usize) -> Self {
+        Self { candidates }
+    }
+}
+
+impl Default for LookaheadBot {
+    fn default() -> Self {
+        Self::new(8)
+    }
+}
+
+impl Strategy for LookaheadBot {
+    fn choose_turn(&mut self, game: &Game) -> Option<Turn> {
+        let player_id = game.current_player_id();
+
+        let mut scored: Vec<_> = game
+            .iter_legal_turns()
+            .filter_map(|turn| simulate(game, turn).map(|after| (turn, after)))
+            .map(|(turn, after)| (evaluate(&after, player_id), turn, after))
+            .collect();
+        scored.sort_by_key(|(score, _, _)| *score);
+        scored.truncate(self.candidates);
+
+        scored
+            .into_iter()
+            .map(|(score, turn, after)| {
+                if score.is_placed() {
+                    return ((score, score), turn);
+                }
+
+                let worst = Rotation::ALL
+                    .into_iter()
+                    .flat_map(|rotation| {
+                        after
+                            .get_board()
+                            .get_shift_options()
+                            .map(move |side_index| (rotation, side_index))
+                    })
+                    .filter_map(|(rotation, side_index)| {
+                        let mut next = after.clone();
+                        next.rotate_free_tile(rotation);
+                        let next_player_id = next.current_player_id();
+                        next.shift_tiles(next_player_id, side_index).ok()?;
+                        Some(evaluate(&next, player_id))
+                    })
+                    .max()
+                    .unwrap_or(score);
+
+                ((worst, score), turn)
+            })
+            .min_by_key(|(key, _)| *key)
+            .map(|(_, turn)| turn)
+    }
+}
@@ -0,0 +1,36 @@
+use game::game::{Game, Turn};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::Strategy;
+
+/// Picks a uniformly random legal turn.
+pub struct RandomBot {
+    rng: ChaCha8Rng,
+}
+
+impl RandomBot {
+    pub fn new() -> Self {
+        Self {
+            rng: ChaCha8Rng::from_entropy(),
+        }
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RandomBot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for RandomBot {
+    fn choose_turn(&mut self, game: &Game) -> Option<Turn> {
+        game.legal_turns().choose(&mut self.rng).copied()
+    }
+}
@@ -0,0 +1,75 @@
+use game::{
+    player::Position,
+    tile::{Rotation, Side, SideIndex},
+};
+
+pub enum Command {
+    Rotate(Rotation),
+    Shift(SideIndex),
+    Move(Position),
+    Reachable,
+    Bot,
+    Help,
+    Quit,
+}
+
+pub const HELP: &str = "\
+Commands:
+  rotate <0|90|180|270>              rotate the free tile
+  shift <top|right|bottom|left> <i>  shift the free tile into row/column i
+  move <x> <y>                       move the current player
+  reachable                          list the positions the player can reach
+  bot                                let the computer play the current turn
+  help                               show this help
+  quit                               leave the game";
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let command = words.next().ok_or("No command given")?;
+        let args: Vec<_> = words.collect();
+
+        match (command, args.as_slice()) {
+            ("rotate" | "r", [rotation]) => parse_rotation(rotation).map(Command::Rotate),
+            ("shift" | "s", [side, index]) => Ok(Command::Shift(SideIndex::new(
+                parse_side(side)?,
+                parse_number(index)?,
+            ))),
+            ("move" | "m", [x, y]) => Ok(Command::Move(Position::new(
+                parse_number(x)?,
+                parse_number(y)?,
+            ))),
+            ("reachable", []) => Ok(Command::Reachable),
+            ("bot", []) => Ok(Command::Bot),
+            ("help" | "h" | "?", []) => Ok(Command::Help),
+            ("quit" | "q" | "exit", []) => Ok(Command::Quit),
+            _ => Err(format!("Unknown command: {}", line.trim())),
+        }
+    }
+}
+
+fn parse_rotation(rotation: &str) -> Result<Rotation, String> {
+    match rotation {
+        "0" => Ok(Rotation::Zero),
+        "90" => Ok(Rotation::Ninety),
+        "180" => Ok(Rotation::OneEighty),
+        "270" => Ok(Rotation::TwoSeventy),
+        _ => Err(format!("Invalid rotation: {}", rotation)),
+    }
+}
+
+fn parse_side(side: &str) -> Result<Side, String> {
+    match side {
+        "top" | "t" => Ok(Side::Top),
+        "right" | "r" => Ok(Side::Right),
+        "bottom" | "b" => Ok(Side::Bottom),
+        "left" | "l" => Ok(Side::Left),
+        _ => Err(format!("Invalid side: {}", side)),
+    }
+}
+
+fn parse_number(number: &str) -> Result<usize, String> {
+    number
+        .parse()
+        .map_err(|_| format!("Invalid number: {}", number))
+}
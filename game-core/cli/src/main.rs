@@ -0,0 +1,226 @@
+mod command;
+mod render;
+
+use std::{
+    env,
+    io::{self, BufRead, Write},
+    process,
+};
+
+use ai::{greedy::GreedyBot, Strategy};
+use game::{
+    error::{ActionKind, ErrorKind, Failure},
+    game::{Game, GameStartSettings},
+};
+
+use crate::{command::Command, render::render};
+
+const USAGE: &str =
+    "Usage: shifty-maze [--players <n>] [--size <side length>] [--width <columns>] [--height <rows>] [--items <per player>] [--seed <seed>]";
+
+/// The most players a game may have, the same limit as on the server.
+const MAX_PLAYERS: usize = 16;
+
+fn main() {
+    let settings = match parse_args(env::args().skip(1)) {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut game = match Game::new(settings) {
+        Ok(game) => game,
+        Err(err) => {
+            eprintln!("{}", Failure::start_game(err));
+            process::exit(1);
+        }
+    };
+
+    println!("{}\n", command::HELP);
+    print!("{}", render(&game));
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match Command::parse(&line) {
+            Ok(Command::Quit) => break,
+            Ok(Command::Help) => println!("{}", command::HELP),
+            Ok(command) => match execute(&mut game, command) {
+                Ok(Some(message)) => println!("{}", message),
+                Ok(None) => print!("{}", render(&game)),
+                Err(err) => println!("{}", err),
+            },
+            Err(err) => println!("{}", err),
+        }
+    }
+}
+
+/// Runs a command against the game. Returns a message to show instead of the
+/// board, if any.
+fn execute(game: &mut Game, command: Command) -> Result<Option<String>, Failure> {
+    let player_id = game.current_player_id();
+
+    match command {
+        Command::Rotate(rotation) => {
+            if !game.rotate_free_tile(rotation) {
+                return Err(Failure::new(
+                    ActionKind::RotateFreeTile,
+                    ErrorKind::GameOver,
+                ));
+            }
+        }
+        Command::Shift(side_index) => {
+            game.shift_tiles(player_id, side_index)
+                .map_err(|err| Failure::shift_tiles(err, player_id, side_index))?;
+        }
+        Command::Move(position) => {
+            game.move_player(player_id, position)
+                .map_err(|err| Failure::move_player(err, player_id, position))?;
+        }
+        Command::Reachable => {
+            let mut reachable = game.currently_reachable().unwrap_or_default();
+            reachable.sort();
+            let positions: Vec<_> = reachable
+                .iter()
+                .map(|p| format!("({}, {})", p.get_x(), p.get_y()))
+                .collect();
+            return Ok(Some(positions.join(" ")));
+        }
+        Command::Bot => {
+            let Some(turn) = GreedyBot.choose_turn(game) else {
+                return Ok(Some("No legal turn available".into()));
+            };
+            game.rotate_free_tile(turn.get_rotation());
+            game.shift_tiles(player_id, turn.get_side_index())
+                .map_err(|err| Failure::shift_tiles(err, player_id, turn.get_side_index()))?;
+            game.move_player(player_id, turn.get_destination())
+                .map_err(|err| Failure::move_player(err, player_id, turn.get_destination()))?;
+        }
+        Command::Help | Command::Quit => {}
+    }
+
+    Ok(None)
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<GameStartSettings, String> {
+    let mut players = 2;
+    let mut width = 7;
+    let mut height = 7;
+    let mut items_per_player = 6;
+    let mut seed = None;
+
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", arg))?;
+        let invalid = || format!("Invalid value for {}: {}", arg, value);
+        let number = || value.parse::<usize>().map_err(|_| invalid());
+
+        match arg.as_str() {
+            "--players" => players = number()?,
+            "--size" => (width, height) = (number()?, number()?),
+            "--width" => width = number()?,
+            "--height" => height = number()?,
+            "--items" => items_per_player = number()?,
+            "--seed" => seed = Some(value.parse::<u64>().map_err(|_| invalid())?),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    if players > MAX_PLAYERS {
+        return Err(format!("At most {} players can play", MAX_PLAYERS));
+    }
+
+    let settings = GameStartSettings::new((0..players).collect(), width, items_per_player)
+        .with_size(width, height);
+    Ok(match seed {
+        Some(seed) => settings.with_seed(seed),
+        None => settings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use game::{
+        board::ItemCount,
+        error::ErrorKind,
+        game::{Game, GameStartSettings},
+        notation::parse_game,
+    };
+
+    use crate::{command::Command, execute, parse_args, render::render};
+
+    #[test]
+    fn parse_commands() {
+        assert!(matches!(
+            Command::parse("rotate 90"),
+            Ok(Command::Rotate(_))
+        ));
+        assert!(matches!(Command::parse("s left 3"), Ok(Command::Shift(_))));
+        assert!(matches!(Command::parse("move 1 2"), Ok(Command::Move(_))));
+        assert!(Command::parse("rotate 45").is_err());
+        assert!(Command::parse("jump").is_err());
+    }
+
+    #[test]
+    fn report_rejected_rotation() {
+        let notation = "L1T0L2/I0T31I1/L0T2L3 I1 0:0,0:0,0::/1:2,0:2,0:: 0 S 0";
+        let mut game = parse_game(notation).unwrap();
+        let rotate = Command::parse("rotate 90").unwrap();
+        let err = execute(&mut game, rotate).unwrap_err();
+        assert_eq!(err.get_kind(), ErrorKind::GameOver);
+    }
+
+    #[test]
+    fn parse_arguments() {
+        let args = ["--players", "3", "--seed", "5"].map(String::from);
+        assert!(parse_args(args.into_iter()).is_ok());
+        assert!(parse_args(["--size".to_string()].into_iter()).is_err());
+        for args in [["--players", "17"], ["--players", "-1"], ["--width", "1.5"]] {
+            assert!(parse_args(args.map(String::from).into_iter()).is_err());
+        }
+    }
+
+    #[test]
+    fn render_wide_board() {
+        let args = ["--width", "9", "--height", "5", "--seed", "2"].map(String::from);
+        let game = Game::new(parse_args(args.into_iter()).unwrap()).unwrap();
+        let rendered = render(&game);
+        assert!(rendered.contains("Player 1 at (8, 0)"));
+        assert!(rendered.lines().any(|line| line.starts_with(" 4")));
+        assert!(!rendered.lines().any(|line| line.starts_with(" 5")));
+    }
+
+    #[test]
+    fn render_board() {
+        let settings = GameStartSettings::new([0, 1].into(), 7, 6).with_seed(3);
+        let rendered = render(&Game::new(settings).unwrap());
+        assert!(rendered.contains("┌@0"));
+        assert!(rendered.contains("Player 1 at (6, 0)"));
+    }
+
+    #[test]
+    fn render_large_ids() {
+        let settings = GameStartSettings::new((0..12).collect(), 11, 1)
+            .with_item_count(ItemCount::Exact { count: 100 })
+            .with_seed(3);
+        let rendered = render(&Game::new(settings).unwrap());
+        let rows: Vec<_> = rendered.lines().skip(2).take(11).collect();
+        assert!(rows.iter().all(|row| row.chars().count() == 4 + 11 * 4 + 1));
+        assert!(rows[0].starts_with(" 0  ") && rows[0].contains("&0 "));
+        assert!(rows[10].starts_with("10  ") && rows[10].contains("&2 "));
+        assert!(rendered.lines().next().unwrap().ends_with("9   10  "));
+    }
+}
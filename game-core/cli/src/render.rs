@@ -0,0 +1,252 @@
+use std::fmt::Write;
+
+use game::{
+    game::{Game, GamePhase, WinCondition},
+    player::Position,
+    tile::{Rotation, Side, Tile, TileVariant},
+};
+
+/// Draws the board with box-drawing characters, followed by the free tile and
+/// the state of every player.
+pub fn render(game: &Game) -> String {
+    let board = game.get_board();
+    let (width, height) = (board.get_width(), board.get_height());
+    let blocked = board.get_free_tile().get_side_index();
+    let is_shiftable = |side: Side, index: usize| {
+        index % 2 == 1 && blocked.is_none_or(|b| b.get_side() != side || b.get_index() != index)
+    };
+
+    let layout = Layout::new(game);
+    let (label, cell) = (layout.label, layout.annotation + 1);
+
+    let mut out = String::new();
+
+    let _ = write!(out, "{:indent$}", "", indent = label + 2);
+    for x in 0..width {
+        let _ = write!(out, "{:<cell$}", x);
+    }
+    out.push('\n');
+    out.push_str(&arrow_row(
+        &layout,
+        width,
+        |x| is_shiftable(Side::Top, x),
+        'v',
+    ));
+
+    for y in 0..height {
+        let _ = write!(
+            out,
+            "{:>label$}{} ",
+            y,
+            arrow(is_shiftable(Side::Left, y), '>')
+        );
+        for x in 0..width {
+            let position = Position::new(x, y);
+            out.push(glyph(&board[position]));
+            let _ = write!(
+                out,
+                "{:<width$}",
+                annotation(game, position),
+                width = layout.annotation
+            );
+        }
+        out.push(arrow(is_shiftable(Side::Right, y), '<'));
+        out.push('\n');
+    }
+
+    out.push_str(&arrow_row(
+        &layout,
+        width,
+        |x| is_shiftable(Side::Bottom, x),
+        '^',
+    ));
+    out.push('\n');
+
+    let free_tile = board.get_free_tile().tile();
+    let _ = writeln!(
+        out,
+        "Free tile: {} ({}){}",
+        glyph(free_tile),
+        rotation_name(free_tile.get_rotation()),
+        free_tile
+            .get_item()
+            .map(|item| format!(" with item {}", item.get_id()))
+            .unwrap_or_default()
+    );
+
+    let current = game.current_player_id();
+    for player in game.get_players().iter() {
+        let next = player
+            .get_next_to_collect()
+            .map(|item| format!("item {}", item.get_id()))
+            .unwrap_or_else(|| match game.get_win_condition() {
+                WinCondition::ReturnToStart => "return to start".into(),
+                WinCondition::ReachCenter => "reach the center".into(),
+                WinCondition::CollectItems { .. } | WinCondition::MostItems { .. } => {
+                    "nothing".into()
+                }
+            });
+        let team = player
+            .get_team()
+            .map(|team| format!(" (team {})", team))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "{} Player {}{} at ({}, {}): {} collected, {} left, next: {}",
+            if player.get_id() == current { '*' } else { ' ' },
+            player.get_id(),
+            team,
+            player.get_position().get_x(),
+            player.get_position().get_y(),
+            player.get_collected().len(),
+            player.get_to_collect().len(),
+            next
+        );
+    }
+
+    let ranks_everyone = matches!(game.get_win_condition(), WinCondition::MostItems { .. });
+    if game.plays_to_end() || ranks_everyone {
+        for standing in game.get_standings() {
+            let _ = writeln!(
+                out,
+                "{}. Player {}: {} collected in {} turns",
+                standing.get_placement(),
+                standing.get_player_id(),
+                standing.get_collected(),
+                standing.get_turns()
+            );
+        }
+    }
+
+    match (game.get_winner(), game.get_phase()) {
+        (Some(winner), _) if game.is_over() => match game.get_winning_team() {
+            Some(team) => {
+                let _ = writeln!(out, "Team {} has won!", team);
+            }
+            None => {
+                let _ = writeln!(out, "Player {} has won!", winner);
+            }
+        },
+        (_, GamePhase::MoveTiles) => out.push_str("Rotate the free tile and shift it in.\n"),
+        (_, GamePhase::MovePlayer) => out.push_str("Move the current player.\n"),
+    }
+
+    out
+}
+
+/// The box-drawing character of the tile's passages. Bridges are drawn heavy
+/// to tell them apart from crosses.
+pub fn glyph(tile: &Tile) -> char {
+    if tile.get_variant() == TileVariant::Bridge {
+        return '╋';
+    }
+
+    let connections = tile.get_connection();
+    let has = |side| connections.contains(&side);
+
+    match (
+        has(Side::Top),
+        has(Side::Right),
+        has(Side::Bottom),
+        has(Side::Left),
+    ) {
+        (true, false, true, false) => '│',
+        (false, true, false, true) => '─',
+        (true, true, false, false) => '└',
+        (false, true, true, false) => '┌',
+        (false, false, true, true) => '┐',
+        (true, false, false, true) => '┘',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┤',
+        (true, true, false, true) => '┴',
+        (true, true, true, false) => '├',
+        (true, true, true, true) => '┼',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╶',
+        (false, false, true, false) => '╷',
+        (false, false, false, true) => '╴',
+        (false, false, false, false) => ' ',
+    }
+}
+
+pub fn rotation_name(rotation: Rotation) -> &'static str {
+    match rotation {
+        Rotation::Zero => "0°",
+        Rotation::Ninety => "90°",
+        Rotation::OneEighty => "180°",
+        Rotation::TwoSeventy => "270°",
+    }
+}
+
+/// The widths of the row labels and of what is shown next to each tile,
+/// sized so that the largest coordinate, player id and item fit.
+struct Layout {
+    label: usize,
+    annotation: usize,
+}
+
+impl Layout {
+    fn new(game: &Game) -> Self {
+        let board = game.get_board();
+        let max_player_id = game.get_players().iter().map(|p| p.get_id()).max();
+        let annotation = [
+            2,
+            digits(board.get_width() - 1),
+            digits(board.get_number_of_items()),
+            1 + max_player_id.map_or(1, digits),
+        ];
+        Self {
+            label: digits(board.get_height() - 1).max(2),
+            annotation: annotation.into_iter().max().unwrap(),
+        }
+    }
+}
+
+fn digits(number: usize) -> usize {
+    number.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
+/// Players are shown as `@` and their id, items as their number. If several
+/// players share a tile, it shows `&` and the lowest of their ids.
+fn annotation(game: &Game, position: Position) -> String {
+    // Players are iterated by id, so the first one has the lowest.
+    let mut players = game
+        .get_players()
+        .iter()
+        .filter(|player| player.get_position() == position)
+        .map(|player| player.get_id());
+
+    match (
+        players.next(),
+        players.next(),
+        game.get_board()[position].get_item(),
+    ) {
+        (Some(player), None, _) => format!("@{}", player),
+        (Some(player), Some(_), _) => format!("&{}", player),
+        (None, _, Some(item)) => item.get_id().to_string(),
+        (None, _, None) => String::new(),
+    }
+}
+
+fn arrow_row(
+    layout: &Layout,
+    width: usize,
+    is_shiftable: impl Fn(usize) -> bool,
+    symbol: char,
+) -> String {
+    let cell = layout.annotation + 1;
+    let mut row = format!("{:indent$}", "", indent = layout.label + 2);
+    for x in 0..width {
+        let _ = write!(row, "{:<cell$}", arrow(is_shiftable(x), symbol));
+    }
+    row.push('\n');
+    row
+}
+
+fn arrow(is_shiftable: bool, symbol: char) -> char {
+    if is_shiftable {
+        symbol
+    } else {
+        ' '
+    }
+}
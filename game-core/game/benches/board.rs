@@ -0,0 +1,104 @@
+//! Benchmarks of the board operations and whole games across board sizes.
+//! Reachability and path finding are benchmarked in `connectivity.rs`.
+//!
+//! Run with `cargo bench -p game --bench board`.
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+use game::{
+    board::Board,
+    game::{Game, GameStartSettings},
+    tile::{Rotation, Side, SideIndex},
+};
+use rand::{seq::IteratorRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+const SIZES: [usize; 4] = [7, 15, 31, 61];
+
+/// Turns after which a random playout is stopped if nobody has won yet.
+const MAX_TURNS: usize = 500;
+
+fn new_game(size: usize, seed: u64) -> Game {
+    let settings = GameStartSettings::new([0, 1, 2, 3].into(), size, 6).with_seed(seed);
+    Game::new(settings).unwrap()
+}
+
+/// Plays random turns until someone wins, returning the number of turns.
+fn random_playout(mut game: Game, rng: &mut impl Rng) -> usize {
+    for turn in 0..MAX_TURNS {
+        if game.is_over() {
+            return turn;
+        }
+
+        let player_id = game.current_player_id();
+        game.rotate_free_tile(*Rotation::ALL.iter().choose(rng).unwrap());
+        let side_index = game.get_board().get_shift_options().choose(rng).unwrap();
+        game.shift_tiles(player_id, side_index).unwrap();
+
+        let destination = game.currently_reachable().unwrap().into_iter().choose(rng);
+        game.move_player(player_id, destination.unwrap()).unwrap();
+    }
+    MAX_TURNS
+}
+
+fn board_new(c: &mut Criterion) {
+    let mut group = c.benchmark_group("board_new");
+    for size in SIZES {
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            b.iter(|| Board::with_rng(black_box(size), black_box(size), &mut rng))
+        });
+    }
+    group.finish();
+}
+
+fn shift_tiles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shift_tiles");
+    for size in SIZES {
+        let board = Board::with_rng(size, size, &mut ChaCha8Rng::seed_from_u64(0)).unwrap();
+        for side in Side::ALL {
+            let side_index = SideIndex::new(side, 1);
+            let id = BenchmarkId::new(format!("{:?}", side), size);
+            group.bench_function(id, |b| {
+                b.iter_batched_ref(
+                    || board.clone(),
+                    |board| board.shift_tiles(black_box(side_index)),
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+fn game_clone(c: &mut Criterion) {
+    let mut group = c.benchmark_group("game_clone");
+    for size in SIZES {
+        let game = new_game(size, 0);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| black_box(&game).clone())
+        });
+    }
+    group.finish();
+}
+
+fn playout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("random_playout");
+    group.sample_size(10);
+    for size in [7, 9, 15] {
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            let (mut seeds, mut rng) = (0.., ChaCha8Rng::seed_from_u64(0));
+            b.iter_batched(
+                || new_game(size, seeds.next().unwrap()),
+                |game| random_playout(game, &mut rng),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, board_new, shift_tiles, game_clone, playout);
+criterion_main!(benches);
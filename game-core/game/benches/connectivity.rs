@@ -0,0 +1,94 @@
+//! Compares the flood fill of `Board::get_reachable` and `Board::get_path`,
+//! which works on connection masks and a bitset, with the breadth-first
+//! search over hash maps it replaced.
+//!
+//! Run with `cargo bench -p game --bench connectivity`.
+
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use game::{board::Board, player::Position};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const SIZES: [usize; 5] = [7, 15, 31, 45, 61];
+
+/// The previous implementation, built on the public API of the board.
+fn hash_map_bfs(
+    board: &Board,
+    start: Position,
+    goal: Option<Position>,
+) -> HashMap<Position, Position> {
+    let mut to_visit: VecDeque<_> = [start].into();
+    let mut preds: HashMap<_, _> = [(start, start)].into();
+
+    while let Some(next) = to_visit.pop_front() {
+        for side in board[next].get_connection() {
+            let Some(neighbour) = board.get_neighbour(next, side) else {
+                continue;
+            };
+            if !board[neighbour].get_connection().contains(&side.opposite()) {
+                continue;
+            }
+            if let Entry::Vacant(entry) = preds.entry(neighbour) {
+                to_visit.push_back(neighbour);
+                entry.insert(next);
+            }
+        }
+        if Some(next) == goal {
+            break;
+        }
+    }
+
+    preds
+}
+
+fn boards() -> impl Iterator<Item = (usize, Board)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    SIZES
+        .into_iter()
+        .map(move |size| (size, Board::with_rng(size, size, &mut rng).unwrap()))
+}
+
+/// The position reachable from the top left corner that is furthest away, so
+/// that the path search cannot stop early.
+fn furthest(board: &Board) -> Position {
+    let start = Position::new(0, 0);
+    *board
+        .get_reachable(start)
+        .iter()
+        .max_by_key(|&&goal| board.get_path(start, goal).map_or(0, |path| path.len()))
+        .unwrap()
+}
+
+fn reachable(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reachable");
+    for (size, board) in boards() {
+        // The center is on a fixed tile, so it is never cut off completely.
+        let start = Position::new(size / 2, size / 2);
+        group.bench_with_input(BenchmarkId::new("bitset", size), &board, |b, board| {
+            b.iter(|| board.get_reachable(black_box(start)))
+        });
+        group.bench_with_input(BenchmarkId::new("hash_map", size), &board, |b, board| {
+            b.iter(|| hash_map_bfs(board, black_box(start), None))
+        });
+    }
+    group.finish();
+}
+
+fn path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("path");
+    for (size, board) in boards() {
+        let (start, goal) = (Position::new(0, 0), furthest(&board));
+        group.bench_with_input(BenchmarkId::new("bitset", size), &board, |b, board| {
+            b.iter(|| board.get_path(black_box(start), black_box(goal)))
+        });
+        group.bench_with_input(BenchmarkId::new("hash_map", size), &board, |b, board| {
+            b.iter(|| hash_map_bfs(board, black_box(start), Some(goal)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, reachable, path);
+criterion_main!(benches);
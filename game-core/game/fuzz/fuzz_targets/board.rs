@@ -0,0 +1,58 @@
+//! Deserializes arbitrary JSON into a `Board` and, if it passes validation,
+//! shifts it in every way and searches it for paths. Nothing may panic and
+//! every shift has to leave the board valid.
+
+#![no_main]
+
+use game::{
+    board::Board,
+    player::Position,
+    tile::{Rotation, Side, SideIndex},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(mut board) = serde_json::from_slice::<Board>(data) else {
+        return;
+    };
+    if board.validate().is_err() {
+        return;
+    }
+
+    let (width, height) = (board.get_width(), board.get_height());
+    let positions: Vec<_> = (0..width)
+        .flat_map(|x| (0..height).map(move |y| Position::new(x, y)))
+        .collect();
+    let outside = Position::new(width, height);
+
+    for &position in &positions {
+        let tile = board[position];
+        if let Some(item) = tile.get_item() {
+            assert_eq!(board.find_item(item), Some(position));
+        }
+        for side in Side::ALL {
+            let _ = board.get_neighbour(position, side);
+        }
+    }
+
+    let start = positions[0];
+    for &goal in positions.iter().chain([&outside]) {
+        let _ = board.get_reachable(goal);
+        let _ = board.get_path(start, goal);
+        let _ = board.get_path(goal, start);
+    }
+
+    board.rotate_free_tile(Rotation::Ninety);
+    let _: Vec<_> = board.get_shift_options().collect();
+    for side in Side::ALL {
+        for index in 0..=width.max(height) {
+            let mut board = board.clone();
+            if let Ok(changes) = board.shift_tiles(SideIndex::new(side, index)) {
+                assert!(changes.values().all(|&p| board.get_tile(p).is_some()));
+            }
+            if let Err(err) = board.validate() {
+                panic!("shift made the board invalid: {}", err);
+            }
+        }
+    }
+});
@@ -0,0 +1,77 @@
+//! Deserializes arbitrary JSON into a `Game` and, if it passes validation,
+//! tries every action on it. Nothing may panic and every action has to leave
+//! the game valid.
+
+#![no_main]
+
+use game::{
+    game::Game,
+    player::Position,
+    tile::{Rotation, Side, SideIndex},
+};
+use libfuzzer_sys::fuzz_target;
+
+fn assert_valid(game: &Game) {
+    if let Err(err) = game.validate() {
+        panic!("action made the game invalid: {}", err);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(game) = serde_json::from_slice::<Game>(data) else {
+        return;
+    };
+    if game.validate().is_err() {
+        return;
+    }
+
+    let board = game.get_board();
+    let (width, height) = (board.get_width(), board.get_height());
+    // Every position on the board and the ones just outside of it.
+    let positions: Vec<_> = (0..=width)
+        .flat_map(|x| (0..=height).map(move |y| Position::new(x, y)))
+        .collect();
+    let player_ids: Vec<_> = game.get_players().iter().map(|p| p.get_id()).collect();
+    let unknown_player = player_ids.iter().max().map_or(0, |id| id + 1);
+
+    let _ = game.currently_reachable();
+    let _ = game.legal_turns();
+
+    for rotation in Rotation::ALL {
+        for side in Side::ALL {
+            for index in 0..=width.max(height) {
+                let mut game = game.clone();
+                game.rotate_free_tile(rotation);
+                let player_id = game.current_player_id();
+                let _ = game.shift_tiles(unknown_player, SideIndex::new(side, index));
+                let _ = game.shift_tiles(player_id, SideIndex::new(side, index));
+                assert_valid(&game);
+
+                for &position in &positions {
+                    let mut game = game.clone();
+                    let _ = game.move_player(unknown_player, position);
+                    let _ = game.move_player(player_id, position);
+                    assert_valid(&game);
+                    let _ = game.currently_reachable();
+                }
+            }
+        }
+    }
+
+    let start = game
+        .get_clock()
+        .and_then(|clock| clock.get_now())
+        .unwrap_or(0);
+    for now in [0, start, start.saturating_add(1000), u64::MAX] {
+        let mut game = game.clone();
+        let _ = game.advance_clock(now);
+        assert_valid(&game);
+    }
+
+    for &player_id in player_ids.iter().chain([&unknown_player]) {
+        let mut game = game.clone();
+        let _ = game.remove_player(player_id);
+        assert_valid(&game);
+        let _ = game.legal_turns();
+    }
+});
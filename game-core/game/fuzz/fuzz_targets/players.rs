@@ -0,0 +1,59 @@
+//! Deserializes arbitrary JSON into `Players` and, if they are valid on a
+//! fixed board, moves and removes every player. Nothing may panic and every
+//! action has to leave the players valid.
+
+#![no_main]
+
+use game::{
+    board::Board,
+    game::WinCondition,
+    player::{Players, Position},
+};
+use libfuzzer_sys::fuzz_target;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(players) = serde_json::from_slice::<Players>(data) else {
+        return;
+    };
+    let board = Board::with_rng(7, 7, &mut ChaCha8Rng::seed_from_u64(0)).unwrap();
+    if players.validate(&board).is_err() {
+        return;
+    }
+
+    let assert_valid = |players: &Players| {
+        if let Err(err) = players.validate(&board) {
+            panic!("action made the players invalid: {}", err);
+        }
+    };
+
+    let _ = players.current_player();
+    let ids: Vec<_> = players.iter().map(|p| p.get_id()).collect();
+    let unknown_player = ids.iter().max().map_or(0, |id| id + 1);
+    let positions: Vec<_> = (0..=7)
+        .flat_map(|x| (0..=7).map(move |y| Position::new(x, y)))
+        .collect();
+
+    for &player_id in ids.iter().chain([&unknown_player]) {
+        for &position in &positions {
+            for win_condition in [
+                WinCondition::ReturnToStart,
+                WinCondition::ReachCenter,
+                WinCondition::CollectItems { count: 1 },
+            ] {
+                let mut players = players.clone();
+                let _ = players.move_player(player_id, position, &board, win_condition);
+                assert_valid(&players);
+            }
+        }
+    }
+
+    // Removes the players one after the other, until the last one is refused.
+    let mut remaining = players.clone();
+    for &player_id in ids.iter().chain([&unknown_player]) {
+        let _ = remaining.remove_player(player_id);
+        assert_valid(&remaining);
+        let _ = remaining.current_player();
+    }
+});
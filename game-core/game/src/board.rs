@@ -1,13 +1,18 @@
 use std::{
-    collections::{hash_map::Entry, HashMap, VecDeque},
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
     iter, mem,
     ops::Index,
 };
 
-use rand::seq::{IteratorRandom, SliceRandom};
+use rand::{
+    seq::{IteratorRandom, SliceRandom},
+    Rng,
+};
 use ts_interop::ts_interop;
 
 use crate::{
+    connectivity,
     player::Position,
     tile::{FreeTile, Item, Rotation, Side, SideIndex, Tile, TileVariant},
 };
@@ -15,9 +20,73 @@ use crate::{
 #[ts_interop]
 #[derive(Clone)]
 pub struct Board {
+    /// The tiles row by row, starting at the top left.
     tiles: Vec<Tile>,
-    side_length: usize,
+    width: usize,
+    height: usize,
     free_tile: FreeTile,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    topology: Topology,
+    /// The number of items on the board, `None` for [`ItemCount::Classic`].
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    number_of_items: Option<usize>,
+}
+
+/// How the edges of the board are connected.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Topology {
+    /// Players cannot walk over the edges of the board.
+    #[default]
+    Bounded,
+    /// Connected tiles on opposite edges are neighbours, so players can walk
+    /// off one edge and come back in on the other.
+    Torus,
+}
+
+/// The relative weights of the tile variants among the movable tiles, or
+/// their exact numbers with [`TileMix::with_exact_counts`]. The fixed tiles
+/// are always T- and L-shapes.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TileMix {
+    l_shape: u32,
+    t_shape: u32,
+    i_shape: u32,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    cross: u32,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    dead_end: u32,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    bridge: u32,
+    /// The weights are the numbers of movable tiles, the free tile included.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    exact_counts: bool,
+}
+
+/// How many items are placed on the board.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(tag = "type")]
+pub enum ItemCount {
+    /// One item on every fixed tile but the corners, and as many on movable
+    /// tiles, as in the original board game.
+    #[default]
+    Classic,
+    Exact {
+        count: usize,
+    },
+    /// The percentage of the tiles that can hold an item, which are all but
+    /// the corners and include the free tile, rounded down.
+    Density {
+        percent: u32,
+    },
 }
 
 pub type PositionMap = HashMap<Position, Position>;
@@ -26,6 +95,41 @@ pub type PositionMap = HashMap<Position, Position>;
 pub enum NewBoardError {
     TooSmall,
     EvenLength,
+    /// The number of tiles overflows.
+    TooLarge,
+    /// Every weight of the [`TileMix`] is zero.
+    InvalidTileMix,
+    /// The exact counts of the [`TileMix`] do not add up to the number of
+    /// movable tiles.
+    WrongTileCount {
+        expected: usize,
+    },
+    /// The [`ItemCount`] leaves the board without items.
+    NoItems,
+    /// The [`ItemCount`] asks for more items than there are tiles that can
+    /// hold one.
+    TooManyItems {
+        max: usize,
+    },
+}
+
+/// Why a board that was not generated by [`Board::new`], e.g. one that was
+/// deserialized, is inconsistent.
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvalidBoardError {
+    /// The width or height is not one a new board could have.
+    InvalidSize,
+    /// The number of tiles does not match the width and height.
+    TileCount,
+    /// The tile ids are not the numbers from zero to the number of tiles on
+    /// the board, each exactly once.
+    TileIds,
+    /// The item is on more than one tile.
+    DuplicateItem(usize),
+    /// The free tile was pushed out at a side index that cannot be shifted.
+    InvalidSideIndex(SideIndex),
+    /// The number of items is zero or more than the tiles can hold.
+    ItemCount,
 }
 
 #[derive(Debug)]
@@ -33,71 +137,157 @@ pub enum ShiftTileError {
     OutOfBounds,
     UnMovable,
     UndoMove,
-}
-
-struct BFSResult {
-    is_reachable: bool,
-    preds: HashMap<Position, Position>,
+    /// A player stands on the tile that would be pushed out. Only returned
+    /// by [`Game::shift_tiles`] under [`PushOffRule::Block`].
+    ///
+    /// [`Game::shift_tiles`]: crate::game::Game::shift_tiles
+    /// [`PushOffRule::Block`]: crate::game::PushOffRule::Block
+    PushesOffPlayer,
 }
 
 impl Board {
-    pub fn new(side_length: usize) -> Result<Self, NewBoardError> {
+    /// Creates a board with `width` columns and `height` rows. Both have to
+    /// be odd, so that every other row and column can be shifted.
+    pub fn new(width: usize, height: usize) -> Result<Self, NewBoardError> {
+        Self::with_rng(width, height, &mut rand::thread_rng())
+    }
+
+    /// Generates the board using the given random number generator, so that
+    /// the same generator state always produces the same board.
+    pub fn with_rng(
+        width: usize,
+        height: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Self, NewBoardError> {
+        Self::with_tile_mix(width, height, TileMix::default(), rng)
+    }
+
+    /// Generates the board like [`Board::with_rng`], but with the movable
+    /// tiles drawn according to the tile mix.
+    pub fn with_tile_mix(
+        width: usize,
+        height: usize,
+        tile_mix: TileMix,
+        rng: &mut impl Rng,
+    ) -> Result<Self, NewBoardError> {
+        Self::with_assortment(width, height, tile_mix, ItemCount::Classic, rng)
+    }
+
+    /// Generates the board like [`Board::with_tile_mix`], but with as many
+    /// items as the item count asks for.
+    pub fn with_assortment(
+        width: usize,
+        height: usize,
+        tile_mix: TileMix,
+        item_count: ItemCount,
+        rng: &mut impl Rng,
+    ) -> Result<Self, NewBoardError> {
         use Rotation::*;
         use TileVariant::*;
 
-        if side_length < 3 {
+        if tile_mix.total() == 0 {
+            return Err(NewBoardError::InvalidTileMix);
+        }
+
+        if width < 3 || height < 3 {
             return Err(NewBoardError::TooSmall);
         }
 
-        if side_length % 2 == 0 {
+        if width.is_multiple_of(2) || height.is_multiple_of(2) {
             return Err(NewBoardError::EvenLength);
         }
 
-        let rotations = [Ninety, TwoSeventy, Zero, OneEighty];
-        let number_of_items = calculate_number_of_items(side_length);
+        // The free tile takes the id after the last tile.
+        let Some(size) = width.checked_mul(height).filter(|&size| size < usize::MAX) else {
+            return Err(NewBoardError::TooLarge);
+        };
 
-        let mut tiles = Vec::with_capacity(side_length.pow(2));
-        let mut movable_tiles = get_tile_assortment(side_length);
+        let num_movable = count_movable_tiles(width, height);
+        if tile_mix.exact_counts && tile_mix.total() != num_movable as u64 {
+            return Err(NewBoardError::WrongTileCount {
+                expected: num_movable,
+            });
+        }
 
-        let mut rng = rand::thread_rng();
-        movable_tiles.shuffle(&mut rng);
+        // The fixed tiles but the corners and the movable ones hold items.
+        let num_fixed = size + 1 - num_movable - 4;
+        let max_items = num_fixed + num_movable;
+        let number_of_items = match item_count {
+            // A 3x3 board only has corners as fixed tiles, so there would be
+            // no items to collect.
+            ItemCount::Classic if num_fixed == 0 => return Err(NewBoardError::TooSmall),
+            ItemCount::Classic => calculate_number_of_items(width, height),
+            ItemCount::Exact { count } => count,
+            ItemCount::Density { percent } if percent > 100 => {
+                return Err(NewBoardError::TooManyItems { max: max_items })
+            }
+            ItemCount::Density { percent } => max_items.saturating_mul(percent as usize) / 100,
+        };
+        if number_of_items == 0 {
+            return Err(NewBoardError::NoItems);
+        }
+        if number_of_items > max_items {
+            return Err(NewBoardError::TooManyItems { max: max_items });
+        }
 
-        let movable_item_indices =
-            (0..movable_tiles.len()).choose_multiple(&mut rng, number_of_items / 2);
+        // Half of the items go on fixed tiles, as long as there are enough of
+        // each.
+        let on_movable = (number_of_items / 2).max(number_of_items.saturating_sub(num_fixed));
+        let on_movable = on_movable.min(num_movable);
+        let on_fixed = number_of_items - on_movable;
+
+        let rotations = [Ninety, TwoSeventy, Zero, OneEighty];
+
+        let mut tiles = Vec::with_capacity(size);
+        let mut movable_tiles = get_tile_assortment(num_movable, tile_mix, rng);
+
+        let movable_item_indices = (0..movable_tiles.len()).choose_multiple(rng, on_movable);
 
         let mut items: Vec<_> = (1..=number_of_items).map(Item::new).collect();
-        items.shuffle(&mut rng);
+        items.shuffle(rng);
+
+        let fixed_item_indices = if on_fixed < num_fixed {
+            (0..num_fixed).choose_multiple(rng, on_fixed)
+        } else {
+            (0..num_fixed).collect()
+        };
+        let mut fixed_index = 0;
+        let mut has_fixed_item = || {
+            fixed_index += 1;
+            fixed_item_indices.contains(&(fixed_index - 1))
+        };
 
         let mut index = 0;
-        for row in 0..side_length {
-            for col in 0..side_length {
+        let (last_row, last_col) = (height - 1, width - 1);
+        for row in 0..height {
+            for col in 0..width {
                 let (variant, rotation, add_item) = match (row, col) {
                     // Corners
                     (0, 0) => (LShape, Ninety, false),
-                    (0, c) if c == side_length - 1 => (LShape, OneEighty, false),
-                    (r, 0) if r == side_length - 1 => (LShape, Zero, false),
-                    (r, c) if r == side_length - 1 && r == c => (LShape, TwoSeventy, false),
+                    (0, c) if c == last_col => (LShape, OneEighty, false),
+                    (r, 0) if r == last_row => (LShape, Zero, false),
+                    (r, c) if r == last_row && c == last_col => (LShape, TwoSeventy, false),
                     // Sides
-                    (r, c) if r == 0 && c % 2 == 0 => (TShape, Zero, true),
-                    (r, c) if c == 0 && r % 2 == 0 => (TShape, TwoSeventy, true),
-                    (r, c) if r == side_length - 1 && c % 2 == 0 => (TShape, OneEighty, true),
-                    (r, c) if c == side_length - 1 && r % 2 == 0 => (TShape, Ninety, true),
+                    (r, c) if r == 0 && c % 2 == 0 => (TShape, Zero, has_fixed_item()),
+                    (r, c) if c == 0 && r % 2 == 0 => (TShape, TwoSeventy, has_fixed_item()),
+                    (r, c) if r == last_row && c % 2 == 0 => (TShape, OneEighty, has_fixed_item()),
+                    (r, c) if c == last_col && r % 2 == 0 => (TShape, Ninety, has_fixed_item()),
                     // Inners
                     (r, c) if r % 2 == 0 && c % 2 == 0 => {
                         index = (index + 1) % rotations.len();
-                        (TShape, rotations[index], true)
+                        (TShape, rotations[index], has_fixed_item())
                     }
                     // Movables
                     _ => (
                         movable_tiles.pop().unwrap(),
-                        *rotations.choose(&mut rng).unwrap(),
+                        *rotations.choose(rng).unwrap(),
                         movable_item_indices.contains(&movable_tiles.len()),
                     ),
                 };
 
                 let item = if add_item { items.pop() } else { None };
 
-                tiles.push(Tile::new(row * side_length + col, variant, rotation, item));
+                tiles.push(Tile::new(row * width + col, variant, rotation, item));
             }
         }
 
@@ -108,68 +298,174 @@ impl Board {
         } else {
             None
         };
-        let free_tile = FreeTile::new(Tile::new(side_length.pow(2), free_tile, Zero, item));
+        let free_tile = FreeTile::new(Tile::new(size, free_tile, Zero, item));
 
         Ok(Self {
             tiles,
-            side_length,
+            width,
+            height,
             free_tile,
+            topology: Topology::Bounded,
+            number_of_items: (item_count != ItemCount::Classic).then_some(number_of_items),
         })
     }
 
-    pub fn get_side_length(&self) -> usize {
-        self.side_length
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub(crate) fn from_parts(
+        tiles: Vec<Tile>,
+        width: usize,
+        height: usize,
+        free_tile: FreeTile,
+        number_of_items: Option<usize>,
+    ) -> Self {
+        Self {
+            tiles,
+            width,
+            height,
+            free_tile,
+            topology: Topology::Bounded,
+            number_of_items,
+        }
+    }
+
+    /// Checks that the board is consistent, so that no action on it can
+    /// panic.
+    pub fn validate(&self) -> Result<(), InvalidBoardError> {
+        let (width, height) = (self.width, self.height);
+        if width < 3 || height < 3 || width.is_multiple_of(2) || height.is_multiple_of(2) {
+            return Err(InvalidBoardError::InvalidSize);
+        }
+
+        if width.checked_mul(height) != Some(self.tiles.len()) {
+            return Err(InvalidBoardError::TileCount);
+        }
+
+        let tiles = || self.tiles.iter().chain(iter::once(self.free_tile.tile()));
+        let mut ids = vec![false; self.tiles.len() + 1];
+        for tile in tiles() {
+            match ids.get_mut(tile.get_id()) {
+                Some(seen @ false) => *seen = true,
+                _ => return Err(InvalidBoardError::TileIds),
+            }
+        }
+
+        let mut items = HashSet::new();
+        if let Some(item) = tiles()
+            .filter_map(Tile::get_item)
+            .find(|item| !items.insert(item.get_id()))
+        {
+            return Err(InvalidBoardError::DuplicateItem(item.get_id()));
+        }
+
+        if let Some(side_index) = self.free_tile.get_side_index() {
+            let index = side_index.get_index();
+            if index >= self.count_lines(side_index.get_side()) || index.is_multiple_of(2) {
+                return Err(InvalidBoardError::InvalidSideIndex(side_index));
+            }
+        }
+
+        let max_items = self.tiles.len() + 1 - 4;
+        if self
+            .number_of_items
+            .is_some_and(|count| count == 0 || count > max_items)
+        {
+            return Err(InvalidBoardError::ItemCount);
+        }
+
+        Ok(())
+    }
+
+    /// The number of columns.
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    /// The middle of the board.
+    pub fn get_center(&self) -> Position {
+        Position::new(self.width / 2, self.height / 2)
+    }
+
+    pub fn get_topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// The number of items if it is not the classic one.
+    pub(crate) fn get_custom_number_of_items(&self) -> Option<usize> {
+        self.number_of_items
     }
 
     pub fn get_number_of_items(&self) -> usize {
-        calculate_number_of_items(self.side_length)
+        self.number_of_items
+            .unwrap_or_else(|| calculate_number_of_items(self.width, self.height))
     }
 
     pub fn get_tile(&self, position: Position) -> Option<&Tile> {
         let x = position.get_x();
         let y = position.get_y();
-        if x >= self.side_length || y >= self.side_length {
+        if x >= self.width || y >= self.height {
             return None;
         }
-        self.tiles.get(x + y * self.side_length)
+        self.tiles.get(x + y * self.width)
     }
 
-    pub fn get_reachable(&self, start: Position) -> Vec<Position> {
-        self.maze_bfs(start, |_| false).preds.into_keys().collect()
+    pub fn get_free_tile(&self) -> &FreeTile {
+        &self.free_tile
     }
 
-    pub fn get_path(&self, start: Position, goal: Position) -> Option<Vec<Position>> {
-        let result = self.maze_bfs(start, |pos| pos == goal);
-        if !result.is_reachable {
-            return None;
-        }
+    /// The position of the tile holding the item, or `None` if it is on the
+    /// free tile.
+    pub fn find_item(&self, item: Item) -> Option<Position> {
+        self.tiles
+            .iter()
+            .position(|tile| tile.get_item() == Some(item))
+            .map(|index| Position::new(index % self.width, index / self.width))
+    }
 
-        let mut path = Vec::new();
-        let mut current = goal;
+    /// Every position reachable from the start, in reading order.
+    pub fn get_reachable(&self, start: Position) -> Vec<Position> {
+        connectivity::reachable(self, start)
+    }
 
-        while current != start {
-            path.push(current);
-            current = result.preds[&current];
-        }
+    /// One of the shortest paths from the start to the goal, both included.
+    pub fn get_path(&self, start: Position, goal: Position) -> Option<Vec<Position>> {
+        connectivity::path(self, start, goal)
+    }
 
-        path.push(start);
-        path.reverse();
-        Some(path)
+    /// All side indices that can currently be used to shift tiles.
+    pub fn get_shift_options(&self) -> impl Iterator<Item = SideIndex> + '_ {
+        Side::ALL
+            .into_iter()
+            .flat_map(|side| {
+                (1..self.count_lines(side))
+                    .step_by(2)
+                    .map(move |i| (side, i))
+            })
+            .map(|(side, index)| SideIndex::new(side, index))
+            .filter(|&side_index| self.free_tile.get_side_index() != Some(side_index))
     }
 
     pub fn rotate_free_tile(&mut self, rotation: Rotation) {
         self.free_tile.set_rotation(rotation);
     }
 
-    pub fn shift_tiles(&mut self, side_index: SideIndex) -> Result<PositionMap, ShiftTileError> {
-        use Side::*;
+    /// The position of the tile that shifting at the side index would push
+    /// out, if the shift is allowed.
+    pub fn get_pushed_out(&self, side_index: SideIndex) -> Result<Position, ShiftTileError> {
         let index = side_index.get_index();
 
-        if index >= self.side_length {
+        if index >= self.count_lines(side_index.get_side()) {
             return Err(ShiftTileError::OutOfBounds);
         }
 
-        if index % 2 == 0 {
+        if index.is_multiple_of(2) {
             return Err(ShiftTileError::UnMovable);
         }
 
@@ -177,134 +473,237 @@ impl Board {
             return Err(ShiftTileError::UndoMove);
         }
 
-        let to_next = |r: usize| (r + 1) % self.side_length;
-        let to_last = |r: usize| r.checked_sub(1).unwrap_or(self.side_length - 1);
-
-        let (last, map) = match side_index.get_side() {
-            side @ (Top | Bottom) => {
-                let mut range = 0..self.side_length - 1;
-                let mut rev = range.clone().rev();
-
-                let (range, to_fn, last): (&mut dyn Iterator<Item = _>, &dyn Fn(usize) -> _, _) =
-                    if side == Bottom {
-                        (
-                            &mut range,
-                            &to_last,
-                            index + self.tiles.len() - self.side_length,
-                        )
-                    } else {
-                        (&mut rev, &to_next, index)
-                    };
-
-                let mut map = HashMap::new();
-                let mut insert =
-                    |col| map.insert(Position::new(index, col), Position::new(index, to_fn(col)));
-
-                for i in range.into_iter() {
-                    let current = index + i * self.side_length;
-                    let next = current + self.side_length;
-                    self.tiles.swap(current, next);
-                    insert(i);
-                }
-
-                insert(self.side_length - 1);
-                (last, map)
-            }
-            side @ (Right | Left) => {
-                let start = index * self.side_length;
-                let end = start + self.side_length - 1;
-                let row = &mut self.tiles[start..=end];
-
-                let (last, to_fn): (_, &dyn Fn(usize) -> _) = if side == Right {
-                    row.rotate_left(1);
-                    (end, &to_last)
-                } else {
-                    row.rotate_right(1);
-                    (start, &to_next)
-                };
+        Ok(match side_index.get_side() {
+            Side::Top => Position::new(index, self.height - 1),
+            Side::Right => Position::new(0, index),
+            Side::Bottom => Position::new(index, 0),
+            Side::Left => Position::new(self.width - 1, index),
+        })
+    }
 
-                (
-                    last,
-                    (0..self.side_length)
-                        .map(|row| (Position::new(row, index), Position::new(to_fn(row), index)))
-                        .collect(),
-                )
-            }
+    pub fn shift_tiles(&mut self, side_index: SideIndex) -> Result<PositionMap, ShiftTileError> {
+        use Side::*;
+        self.get_pushed_out(side_index)?;
+
+        // The positions of the shifted row or column, in shifting direction.
+        let index = side_index.get_index();
+        let side = side_index.get_side();
+        let mut line: Vec<_> = match side {
+            Top | Bottom => (0..self.height).map(|y| Position::new(index, y)).collect(),
+            Right | Left => (0..self.width).map(|x| Position::new(x, index)).collect(),
         };
+        if matches!(side, Bottom | Right) {
+            line.reverse();
+        }
 
-        mem::swap(&mut self.tiles[last], self.free_tile.tile_mut());
+        // Moves the last tile of the line to the front, then swaps it with
+        // the free tile.
+        for i in (0..line.len() - 1).rev() {
+            let (current, next) = (self.index_of(line[i]), self.index_of(line[i + 1]));
+            self.tiles.swap(current, next);
+        }
+        let first = self.index_of(line[0]);
+        mem::swap(&mut self.tiles[first], self.free_tile.tile_mut());
         self.free_tile.set_side_index(side_index.shift());
 
-        Ok(map)
+        // Players move along with their tile. Players pushed off the board
+        // end up on the inserted tile, as if the board wrapped around. Games
+        // that forbid pushing players off check before shifting.
+        let direction = side.opposite();
+        Ok(line
+            .into_iter()
+            .map(|position| (position, self.wrap(position, direction)))
+            .collect())
     }
 
-    fn neighbours(&self, position: Position) -> Vec<Position> {
-        let mut neighbours = Vec::new();
+    /// The position next to the given one. At the edge of the board, this is
+    /// `None`, unless the board is a torus.
+    pub fn get_neighbour(&self, position: Position, side: Side) -> Option<Position> {
+        if self.is_at_edge(position, side) && self.topology == Topology::Bounded {
+            None
+        } else {
+            Some(self.wrap(position, side))
+        }
+    }
 
-        if let Some(tile) = self.get_tile(position) {
-            for side in tile.get_connection() {
-                match side {
-                    Side::Top => {
-                        if let Some(top) = position.top(&self) {
-                            if self[top].get_connection().contains(&Side::Bottom) {
-                                neighbours.push(top);
-                            }
-                        }
-                    }
-                    Side::Right => {
-                        if let Some(right) = position.right(&self) {
-                            if self[right].get_connection().contains(&Side::Left) {
-                                neighbours.push(right);
-                            }
-                        }
-                    }
-                    Side::Bottom => {
-                        if let Some(bottom) = position.bottom(&self) {
-                            if self[bottom].get_connection().contains(&Side::Top) {
-                                neighbours.push(bottom);
-                            }
-                        }
-                    }
-                    Side::Left => {
-                        if let Some(left) = position.left(&self) {
-                            if self[left].get_connection().contains(&Side::Right) {
-                                neighbours.push(left);
-                            }
-                        }
-                    }
-                }
-            }
+    /// Whether the position is on the edge of the board at the given side.
+    pub(crate) fn is_at_edge(&self, position: Position, side: Side) -> bool {
+        match side {
+            Side::Top => position.get_y() == 0,
+            Side::Right => position.get_x() + 1 == self.width,
+            Side::Bottom => position.get_y() + 1 == self.height,
+            Side::Left => position.get_x() == 0,
+        }
+    }
+
+    /// The position next to the given one, continuing on the opposite edge
+    /// of the board if it leaves it.
+    fn wrap(&self, position: Position, side: Side) -> Position {
+        let (x, y) = (position.get_x(), position.get_y());
+        match side {
+            Side::Top => Position::new(x, y.checked_sub(1).unwrap_or(self.height - 1)),
+            Side::Right => Position::new((x + 1) % self.width, y),
+            Side::Bottom => Position::new(x, (y + 1) % self.height),
+            Side::Left => Position::new(x.checked_sub(1).unwrap_or(self.width - 1), y),
+        }
+    }
+
+    fn index_of(&self, position: Position) -> usize {
+        position.get_x() + position.get_y() * self.width
+    }
+
+    /// The number of rows or columns that can be shifted in from the side.
+    fn count_lines(&self, side: Side) -> usize {
+        match side {
+            Side::Top | Side::Bottom => self.width,
+            Side::Right | Side::Left => self.height,
+        }
+    }
+}
+
+impl TileMix {
+    /// The variants in the order tiles lost to rounding are handed out.
+    const VARIANTS: [TileVariant; 6] = [
+        TileVariant::LShape,
+        TileVariant::TShape,
+        TileVariant::IShape,
+        TileVariant::Cross,
+        TileVariant::DeadEnd,
+        TileVariant::Bridge,
+    ];
+
+    /// A mix of only the classic tiles.
+    pub fn new(l_shape: u32, t_shape: u32, i_shape: u32) -> Self {
+        Self {
+            l_shape,
+            t_shape,
+            i_shape,
+            cross: 0,
+            dead_end: 0,
+            bridge: 0,
+            exact_counts: false,
+        }
+    }
+
+    pub fn with_cross(self, cross: u32) -> Self {
+        Self { cross, ..self }
+    }
+
+    pub fn with_dead_end(self, dead_end: u32) -> Self {
+        Self { dead_end, ..self }
+    }
+
+    pub fn with_bridge(self, bridge: u32) -> Self {
+        Self { bridge, ..self }
+    }
+
+    /// Takes the weights as the exact numbers of movable tiles, which have to
+    /// add up to all of them, the free tile included.
+    pub fn with_exact_counts(self, exact_counts: bool) -> Self {
+        Self {
+            exact_counts,
+            ..self
+        }
+    }
+
+    pub fn has_exact_counts(&self) -> bool {
+        self.exact_counts
+    }
+
+    pub fn get_weight(&self, variant: TileVariant) -> u32 {
+        match variant {
+            TileVariant::LShape => self.l_shape,
+            TileVariant::TShape => self.t_shape,
+            TileVariant::IShape => self.i_shape,
+            TileVariant::Cross => self.cross,
+            TileVariant::DeadEnd => self.dead_end,
+            TileVariant::Bridge => self.bridge,
         }
+    }
 
-        neighbours
+    fn total(&self) -> u64 {
+        Self::VARIANTS
+            .iter()
+            .map(|&variant| u64::from(self.get_weight(variant)))
+            .sum()
     }
+}
 
-    fn maze_bfs(&self, start: Position, goal_fn: impl Fn(Position) -> bool) -> BFSResult {
-        let mut to_visit: VecDeque<_> = [start].into();
-        let mut preds: HashMap<_, _> = [(start, start)].into();
+impl Default for TileMix {
+    /// The mix of the original board game.
+    fn default() -> Self {
+        Self::new(15, 6, 13)
+    }
+}
 
-        while let Some(next) = to_visit.pop_front() {
-            for neighbour in self.neighbours(next) {
-                if let Entry::Vacant(entry) = preds.entry(neighbour) {
-                    to_visit.push_back(neighbour);
-                    entry.insert(next);
-                }
+impl Display for NewBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewBoardError::TooSmall => f.write_str("Side length too small"),
+            NewBoardError::EvenLength => f.write_str("Side length has to be odd"),
+            NewBoardError::TooLarge => f.write_str("Board too large"),
+            NewBoardError::InvalidTileMix => {
+                f.write_str("Tile mix needs at least one tile variant")
             }
-            if goal_fn(next) {
-                return BFSResult {
-                    is_reachable: true,
-                    preds,
-                };
+            NewBoardError::WrongTileCount { expected } => {
+                write!(
+                    f,
+                    "Tile counts have to add up to {} movable tiles",
+                    expected
+                )
+            }
+            NewBoardError::NoItems => f.write_str("Board needs at least one item"),
+            NewBoardError::TooManyItems { max } => {
+                write!(f, "Board can hold at most {} items", max)
             }
         }
+    }
+}
+
+impl std::error::Error for NewBoardError {}
 
-        BFSResult {
-            is_reachable: false,
-            preds,
+impl Display for InvalidBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidBoardError::InvalidSize => {
+                f.write_str("Width and height have to be odd and at least 3")
+            }
+            InvalidBoardError::TileCount => {
+                f.write_str("Number of tiles does not match the board size")
+            }
+            InvalidBoardError::TileIds => f.write_str("Tile ids are not unique"),
+            InvalidBoardError::DuplicateItem(item) => {
+                write!(f, "Item {} is on more than one tile", item)
+            }
+            InvalidBoardError::ItemCount => f.write_str("Number of items does not fit the board"),
+            InvalidBoardError::InvalidSideIndex(side_index) => {
+                write!(
+                    f,
+                    "Free tile cannot have been pushed out at {:?}",
+                    side_index
+                )
+            }
         }
     }
 }
 
+impl std::error::Error for InvalidBoardError {}
+
+impl Display for ShiftTileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ShiftTileError::OutOfBounds => "No such row/column exists",
+            ShiftTileError::UnMovable => "Specified row/column is not movable",
+            ShiftTileError::UndoMove => {
+                "Tile cannot be pushed back in where it was previously pushed out"
+            }
+            ShiftTileError::PushesOffPlayer => "Shift would push a player off the board",
+        })
+    }
+}
+
+impl std::error::Error for ShiftTileError {}
+
 impl Index<Position> for Board {
     type Output = Tile;
 
@@ -313,37 +712,53 @@ impl Index<Position> for Board {
     }
 }
 
-fn get_tile_assortment(side_length: usize) -> Vec<TileVariant> {
-    const T_RATIO: f64 = 6. / 34.;
-    const I_RATIO: f64 = 13. / 34.;
-    const L_RATIO: f64 = 15. / 34.;
-
-    let num_moveable = side_length.pow(2) - (side_length / 2 + 1).pow(2) + 1;
-    let num_i_tiles = (num_moveable as f64 * I_RATIO) as usize; // flooring
-    let mut num_t_tiles = (num_moveable as f64 * T_RATIO) as usize;
-    let mut num_l_tiles = (num_moveable as f64 * L_RATIO) as usize;
-
-    match num_moveable - (num_t_tiles + num_i_tiles + num_l_tiles) {
-        diff @ (1 | 2) => {
-            num_l_tiles += 1;
-            if diff == 2 {
-                num_t_tiles += 1;
-            }
+/// The tiles that are not fixed, the free tile included.
+fn count_movable_tiles(width: usize, height: usize) -> usize {
+    width * height - (width / 2 + 1) * (height / 2 + 1) + 1
+}
+
+fn get_tile_assortment(
+    num_moveable: usize,
+    tile_mix: TileMix,
+    rng: &mut impl Rng,
+) -> Vec<TileVariant> {
+    use TileVariant::*;
+
+    let total = tile_mix.total() as f64;
+    let mut counts = TileMix::VARIANTS.map(|variant| {
+        let weight = tile_mix.get_weight(variant) as usize;
+        if tile_mix.exact_counts {
+            return (variant, weight);
+        }
+        let ratio = weight as f64 / total;
+        (variant, (num_moveable as f64 * ratio) as usize) // flooring
+    });
+
+    // Hand out the tiles lost to flooring one by one, in the order of
+    // `TileMix::VARIANTS`.
+    let mut missing = num_moveable - counts.iter().map(|(_, count)| count).sum::<usize>();
+    for i in (0..counts.len()).cycle() {
+        if missing == 0 {
+            break;
+        }
+        let (variant, count) = &mut counts[i];
+        if tile_mix.get_weight(*variant) > 0 {
+            *count += 1;
+            missing -= 1;
         }
-        _ => {}
     }
 
-    assert!(num_t_tiles + num_i_tiles + num_l_tiles == num_moveable);
-
     let mut movable_tiles = Vec::with_capacity(num_moveable);
-    movable_tiles.extend(iter::repeat(TileVariant::TShape).take(num_t_tiles));
-    movable_tiles.extend(iter::repeat(TileVariant::IShape).take(num_i_tiles));
-    movable_tiles.extend(iter::repeat(TileVariant::LShape).take(num_l_tiles));
+    for variant in [TShape, IShape, LShape, Cross, DeadEnd, Bridge] {
+        let (_, count) = counts.iter().find(|(other, _)| *other == variant).unwrap();
+        movable_tiles.extend(iter::repeat_n(variant, *count));
+    }
+    movable_tiles.shuffle(rng);
 
     movable_tiles
 }
 
-fn calculate_number_of_items(side_length: usize) -> usize {
-    // 2 * (ceil(side_length / 2)² - num_corners)
-    2 * ((side_length / 2 + 1).pow(2) - 4)
+fn calculate_number_of_items(width: usize, height: usize) -> usize {
+    // 2 * (ceil(width / 2) * ceil(height / 2) - num_corners)
+    2 * ((width / 2 + 1) * (height / 2 + 1) - 4)
 }
@@ -0,0 +1,126 @@
+//! Time limits for games. The engine has no notion of the current time;
+//! callers pass timestamps in milliseconds to [`Game::advance_clock`], which
+//! makes timed games deterministic and easy to test.
+//!
+//! [`Game::advance_clock`]: crate::game::Game::advance_clock
+
+use ts_interop::ts_interop;
+
+use crate::player::Player;
+
+/// The time limits of a game, in milliseconds.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TimeControl {
+    /// The time for every single turn.
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    turn_limit: Option<u64>,
+    /// The time of every player for the whole game, like a chess clock.
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    total_time: Option<u64>,
+    penalty: TimeoutPenalty,
+}
+
+/// What happens to a player who runs out of time.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TimeoutPenalty {
+    /// The turn ends where it is: tiles that have not been shifted yet stay
+    /// as they are, and the player does not move.
+    SkipMove,
+    /// Tiles that have not been shifted yet are shifted at a random side
+    /// index, then the turn ends without the player moving.
+    RandomShift,
+    /// The player is removed from the game.
+    Forfeit,
+}
+
+/// The clock of a timed game.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Clock {
+    time_control: TimeControl,
+    /// The timestamp the clock was last advanced to, if it was started.
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    now: Option<u64>,
+    /// The time the current player has used in this turn.
+    turn_time: u64,
+}
+
+impl TimeControl {
+    pub fn new(turn_limit: Option<u64>, total_time: Option<u64>, penalty: TimeoutPenalty) -> Self {
+        Self {
+            turn_limit,
+            total_time,
+            penalty,
+        }
+    }
+
+    pub fn get_turn_limit(&self) -> Option<u64> {
+        self.turn_limit
+    }
+
+    pub fn get_total_time(&self) -> Option<u64> {
+        self.total_time
+    }
+
+    pub fn get_penalty(&self) -> TimeoutPenalty {
+        self.penalty
+    }
+}
+
+impl Clock {
+    pub(crate) fn new(time_control: TimeControl) -> Self {
+        Self {
+            time_control,
+            now: None,
+            turn_time: 0,
+        }
+    }
+
+    pub fn get_time_control(&self) -> TimeControl {
+        self.time_control
+    }
+
+    pub fn get_now(&self) -> Option<u64> {
+        self.now
+    }
+
+    /// The time the current player has used in this turn.
+    pub fn get_turn_time(&self) -> u64 {
+        self.turn_time
+    }
+
+    /// The time the player has left in the current turn, or `None` if it is
+    /// not limited.
+    pub fn get_time_left(&self, player: &Player) -> Option<u64> {
+        let turn = self
+            .time_control
+            .turn_limit
+            .map(|limit| limit.saturating_sub(self.turn_time));
+        match (turn, player.get_remaining_time()) {
+            (Some(turn), Some(total)) => Some(turn.min(total)),
+            (turn, total) => turn.or(total),
+        }
+    }
+
+    /// Moves the clock to the timestamp and returns the time that passed.
+    /// The first call only starts the clock, and the clock never goes back.
+    pub(crate) fn advance(&mut self, now: u64) -> u64 {
+        let last = self.now.unwrap_or(now);
+        self.now = Some(last.max(now));
+        now.saturating_sub(last)
+    }
+
+    /// Charges the time to the current turn and the player's total time.
+    pub(crate) fn charge(&mut self, player: &mut Player, time: u64) {
+        self.turn_time = self.turn_time.saturating_add(time);
+        if let Some(remaining) = player.get_remaining_time() {
+            player.set_remaining_time(Some(remaining.saturating_sub(time)));
+        }
+    }
+
+    pub(crate) fn start_turn(&mut self) {
+        self.turn_time = 0;
+    }
+}
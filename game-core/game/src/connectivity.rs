@@ -0,0 +1,166 @@
+//! Reachability on the board using connection masks and a bitset.
+//!
+//! The flood fill only touches the tiles it reaches: connections are checked
+//! with the 4-bit masks of [`Tile::get_connection_mask`], visited tiles are
+//! marked in a bitset indexed like the tiles of the board, and the queue of
+//! the breadth-first search doubles as the record of predecessors. Nothing is
+//! hashed and nothing is allocated per tile.
+//!
+//! Bridges are the one tile that can be crossed twice, once along each of its
+//! passages, so the search visits states instead of tiles: every tile has one
+//! state per axis, of which only bridges use the second. The tiles of visited
+//! states are collected in a second bitset.
+//!
+//! [`Tile::get_connection_mask`]: crate::tile::Tile::get_connection_mask
+
+use crate::{
+    board::Board,
+    player::Position,
+    tile::{Side, TileVariant},
+};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+struct BitSet {
+    words: Vec<u64>,
+}
+
+/// A visited tile, the side it was entered at and the position in the queue
+/// of the tile it was reached from.
+#[derive(Clone, Copy)]
+struct Visit {
+    position: Position,
+    entered_at: Option<Side>,
+    pred: usize,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// Adds the index and returns whether it was not yet in the set.
+    fn insert(&mut self, index: usize) -> bool {
+        let (word, bit) = (&mut self.words[index / WORD_BITS], 1 << (index % WORD_BITS));
+        let is_new = *word & bit == 0;
+        *word |= bit;
+        is_new
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * WORD_BITS + bit)
+            })
+        })
+    }
+}
+
+/// Every position reachable from the start, in reading order.
+pub(crate) fn reachable(board: &Board, start: Position) -> Vec<Position> {
+    if board.get_tile(start).is_none() {
+        return vec![start];
+    }
+
+    let (visited, _) = flood_fill(board, start, None);
+    let width = board.get_width();
+    visited
+        .iter()
+        .map(|index| Position::new(index % width, index / width))
+        .collect()
+}
+
+/// One of the shortest paths from the start to the goal, both included.
+pub(crate) fn path(board: &Board, start: Position, goal: Position) -> Option<Vec<Position>> {
+    if start == goal {
+        return Some(vec![start]);
+    }
+    board.get_tile(start)?;
+
+    let (_, queue) = flood_fill(board, start, Some(goal));
+    let last = queue.last()?;
+    if last.position != goal {
+        return None;
+    }
+
+    let mut path = vec![goal];
+    let mut visit = *last;
+    while visit.position != start {
+        visit = queue[visit.pred];
+        path.push(visit.position);
+    }
+
+    path.reverse();
+    Some(path)
+}
+
+/// Breadth-first search from the start, stopping early once the goal is
+/// reached. Returns the visited tiles and the queue in visiting order.
+fn flood_fill(board: &Board, start: Position, goal: Option<Position>) -> (BitSet, Vec<Visit>) {
+    let width = board.get_width();
+    let index = |position: Position| position.get_x() + position.get_y() * width;
+    let mask = |position: Position| board[position].get_connection_mask();
+    // Bridges entered from the left or right are in the second state.
+    let state = |position: Position, entered_at: Side| {
+        let is_crossing = board[position].get_variant() == TileVariant::Bridge
+            && matches!(entered_at, Side::Left | Side::Right);
+        2 * index(position) + usize::from(is_crossing)
+    };
+
+    let len = width * board.get_height();
+    let mut visited = BitSet::new(len);
+    let mut states = BitSet::new(2 * len);
+    visited.insert(index(start));
+    states.insert(2 * index(start));
+    let mut queue = vec![Visit {
+        position: start,
+        entered_at: None,
+        pred: 0,
+    }];
+
+    let mut head = 0;
+    while let Some(&Visit {
+        position,
+        entered_at,
+        ..
+    }) = queue.get(head)
+    {
+        if Some(position) == goal {
+            queue.truncate(head + 1);
+            break;
+        }
+
+        let exits = board[position].get_exit_mask(entered_at);
+        for side in Side::ALL {
+            if exits & side.mask() == 0 {
+                continue;
+            }
+            let Some(neighbour) = board.get_neighbour(position, side) else {
+                continue;
+            };
+            let entered_at = side.opposite();
+            if mask(neighbour) & entered_at.mask() != 0
+                && states.insert(state(neighbour, entered_at))
+            {
+                visited.insert(index(neighbour));
+                queue.push(Visit {
+                    position: neighbour,
+                    entered_at: Some(entered_at),
+                    pred: head,
+                });
+            }
+        }
+
+        head += 1;
+    }
+
+    (visited, queue)
+}
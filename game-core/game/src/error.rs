@@ -0,0 +1,315 @@
+use std::fmt::{self, Display};
+
+use ts_interop::ts_interop;
+
+use crate::{
+    board::{InvalidBoardError, NewBoardError, ShiftTileError},
+    game::{GameError, InvalidGameError, NewGameError},
+    player::{InvalidPlayersError, MoveError, PlayerId, Position, RemovePlayerError},
+    tile::SideIndex,
+};
+
+/// A failed action together with the reason it failed.
+///
+/// Unlike the error types of the individual actions, this can be sent to the
+/// front end, which can branch on the `kind` and its context instead of
+/// parsing the `message`.
+#[ts_interop]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Failure {
+    action: ActionKind,
+    kind: ErrorKind,
+    /// English description of the failure, e.g. for logging.
+    message: String,
+}
+
+/// The action that was attempted.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActionKind {
+    StartGame,
+    RotateFreeTile,
+    ShiftTiles,
+    MovePlayer,
+    RemovePlayer,
+    AdvanceClock,
+    GetLastPath,
+    GetLastEvents,
+    GetView,
+    UndoMove,
+    RedoMove,
+    PlayComputerTurn,
+    GetReplay,
+    LoadReplay,
+    SetGame,
+}
+
+/// The reason an action failed. The variant name is its stable code.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(tag = "code", content = "context")]
+pub enum ErrorKind {
+    NotStarted,
+    GameOver,
+    ShiftTilesFirst,
+    MovePlayerFirst,
+    NotEnoughPlayers,
+    InvalidTeams,
+    InvalidWinCondition,
+    SideLengthTooSmall,
+    EvenSideLength,
+    BoardTooLarge,
+    InvalidTileMix,
+    WrongTileCount { expected: usize },
+    NoItems,
+    TooManyItems { max: usize },
+    OutOfBounds { side_index: SideIndex },
+    UnMovable { side_index: SideIndex },
+    UndoMove { side_index: SideIndex },
+    PushesOffPlayer { side_index: SideIndex },
+    InvalidPosition { position: Position },
+    InvalidPlayer { player_id: PlayerId },
+    NotYourTurn { player_id: PlayerId },
+    UnreachablePosition { position: Position },
+    NotMovedYet,
+    NoHistory,
+    NoRedo,
+    NoLegalTurn,
+    NoReplay,
+    ReplayTooShort,
+    InvalidGame { reason: InvalidGameReason },
+}
+
+/// Why a game that was handed over is inconsistent, see
+/// [`InvalidGameError`].
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(tag = "code", content = "context")]
+pub enum InvalidGameReason {
+    InvalidSize,
+    TileCount,
+    TileIds,
+    DuplicateItem { item: usize },
+    InvalidSideIndex { side_index: SideIndex },
+    ItemCount,
+    NoPlayers,
+    MismatchedId { player_id: PlayerId },
+    UnknownTurn { player_id: PlayerId },
+    OffBoard { player_id: PlayerId },
+    UnknownItem { player_id: PlayerId },
+    NotEnoughPlayers,
+    UnknownWinner { player_id: PlayerId },
+    MixedTeams,
+    RemainingTime { player_id: PlayerId },
+    TurnTime,
+}
+
+impl Failure {
+    pub fn new(action: ActionKind, kind: ErrorKind) -> Self {
+        Self {
+            action,
+            kind,
+            message: format!("{}: {}", action, kind),
+        }
+    }
+
+    pub fn start_game(err: NewGameError) -> Self {
+        let kind = match err {
+            NewGameError::PlayerError => ErrorKind::NotEnoughPlayers,
+            NewGameError::TeamError => ErrorKind::InvalidTeams,
+            NewGameError::WinConditionError => ErrorKind::InvalidWinCondition,
+            NewGameError::BoardError(NewBoardError::TooSmall) => ErrorKind::SideLengthTooSmall,
+            NewGameError::BoardError(NewBoardError::EvenLength) => ErrorKind::EvenSideLength,
+            NewGameError::BoardError(NewBoardError::TooLarge) => ErrorKind::BoardTooLarge,
+            NewGameError::BoardError(NewBoardError::InvalidTileMix) => ErrorKind::InvalidTileMix,
+            NewGameError::BoardError(NewBoardError::WrongTileCount { expected }) => {
+                ErrorKind::WrongTileCount { expected }
+            }
+            NewGameError::BoardError(NewBoardError::NoItems) => ErrorKind::NoItems,
+            NewGameError::BoardError(NewBoardError::TooManyItems { max }) => {
+                ErrorKind::TooManyItems { max }
+            }
+        };
+        Self::new(ActionKind::StartGame, kind)
+    }
+
+    pub fn shift_tiles(
+        err: GameError<ShiftTileError>,
+        player_id: PlayerId,
+        side_index: SideIndex,
+    ) -> Self {
+        let kind = match err {
+            GameError::GameOver => ErrorKind::GameOver,
+            GameError::StateError => ErrorKind::MovePlayerFirst,
+            GameError::NotYourTurn => ErrorKind::NotYourTurn { player_id },
+            GameError::ActionError(ShiftTileError::OutOfBounds) => {
+                ErrorKind::OutOfBounds { side_index }
+            }
+            GameError::ActionError(ShiftTileError::UnMovable) => {
+                ErrorKind::UnMovable { side_index }
+            }
+            GameError::ActionError(ShiftTileError::UndoMove) => ErrorKind::UndoMove { side_index },
+            GameError::ActionError(ShiftTileError::PushesOffPlayer) => {
+                ErrorKind::PushesOffPlayer { side_index }
+            }
+        };
+        Self::new(ActionKind::ShiftTiles, kind)
+    }
+
+    pub fn move_player(err: GameError<MoveError>, player_id: PlayerId, position: Position) -> Self {
+        let kind = match err {
+            GameError::GameOver => ErrorKind::GameOver,
+            GameError::StateError => ErrorKind::ShiftTilesFirst,
+            GameError::NotYourTurn => ErrorKind::NotYourTurn { player_id },
+            GameError::ActionError(MoveError::InvalidPosition) => {
+                ErrorKind::InvalidPosition { position }
+            }
+            GameError::ActionError(MoveError::InvalidPlayer) => {
+                ErrorKind::InvalidPlayer { player_id }
+            }
+            GameError::ActionError(MoveError::UnreachablePosition) => {
+                ErrorKind::UnreachablePosition { position }
+            }
+        };
+        Self::new(ActionKind::MovePlayer, kind)
+    }
+
+    pub fn remove_player(err: GameError<RemovePlayerError>, player_id: PlayerId) -> Self {
+        let kind = match err {
+            GameError::ActionError(RemovePlayerError::InvalidPlayer) => {
+                ErrorKind::InvalidPlayer { player_id }
+            }
+            _ => ErrorKind::GameOver,
+        };
+        Self::new(ActionKind::RemovePlayer, kind)
+    }
+
+    pub fn set_game(err: InvalidGameError) -> Self {
+        let message = format!("{}: {}", ActionKind::SetGame, err);
+        let reason = match err {
+            InvalidGameError::BoardError(err) => match err {
+                InvalidBoardError::InvalidSize => InvalidGameReason::InvalidSize,
+                InvalidBoardError::TileCount => InvalidGameReason::TileCount,
+                InvalidBoardError::TileIds => InvalidGameReason::TileIds,
+                InvalidBoardError::DuplicateItem(item) => InvalidGameReason::DuplicateItem { item },
+                InvalidBoardError::InvalidSideIndex(side_index) => {
+                    InvalidGameReason::InvalidSideIndex { side_index }
+                }
+                InvalidBoardError::ItemCount => InvalidGameReason::ItemCount,
+            },
+            InvalidGameError::PlayersError(err) => match err {
+                InvalidPlayersError::NoPlayers => InvalidGameReason::NoPlayers,
+                InvalidPlayersError::MismatchedId(player_id) => {
+                    InvalidGameReason::MismatchedId { player_id }
+                }
+                InvalidPlayersError::UnknownTurn(player_id) => {
+                    InvalidGameReason::UnknownTurn { player_id }
+                }
+                InvalidPlayersError::OffBoard(player_id) => {
+                    InvalidGameReason::OffBoard { player_id }
+                }
+                InvalidPlayersError::UnknownItem(player_id) => {
+                    InvalidGameReason::UnknownItem { player_id }
+                }
+            },
+            InvalidGameError::NotEnoughPlayers => InvalidGameReason::NotEnoughPlayers,
+            InvalidGameError::UnknownWinner(player_id) => {
+                InvalidGameReason::UnknownWinner { player_id }
+            }
+            InvalidGameError::MixedTeams => InvalidGameReason::MixedTeams,
+            InvalidGameError::RemainingTime(player_id) => {
+                InvalidGameReason::RemainingTime { player_id }
+            }
+            InvalidGameError::TurnTime => InvalidGameReason::TurnTime,
+        };
+        Self {
+            action: ActionKind::SetGame,
+            kind: ErrorKind::InvalidGame { reason },
+            message,
+        }
+    }
+
+    pub fn with_action(self, action: ActionKind) -> Self {
+        Self::new(action, self.kind)
+    }
+
+    pub fn get_action(&self) -> ActionKind {
+        self.action
+    }
+
+    pub fn get_kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ActionKind::StartGame => "Cannot start game",
+            ActionKind::RotateFreeTile => "Cannot rotate tile",
+            ActionKind::ShiftTiles => "Cannot shift tiles",
+            ActionKind::MovePlayer => "Cannot move player",
+            ActionKind::RemovePlayer => "Cannot remove player",
+            ActionKind::AdvanceClock => "Cannot advance clock",
+            ActionKind::GetLastPath => "Cannot get last path",
+            ActionKind::GetLastEvents => "Cannot get last events",
+            ActionKind::GetView => "Cannot get view",
+            ActionKind::UndoMove => "Cannot undo move",
+            ActionKind::RedoMove => "Cannot redo move",
+            ActionKind::PlayComputerTurn => "Cannot play computer turn",
+            ActionKind::GetReplay => "Cannot get replay",
+            ActionKind::LoadReplay => "Cannot load replay",
+            ActionKind::SetGame => "Cannot set game",
+        })
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ErrorKind::NotStarted => "Game not started",
+            ErrorKind::GameOver => "Game has ended",
+            ErrorKind::ShiftTilesFirst => "Player has to shift tiles first",
+            ErrorKind::MovePlayerFirst => "Player has to end turn by moving figure",
+            ErrorKind::NotEnoughPlayers => "Not enough players",
+            ErrorKind::InvalidTeams => {
+                "Every player has to be in exactly one of at least two teams"
+            }
+            ErrorKind::InvalidWinCondition => "Win condition cannot be met",
+            ErrorKind::SideLengthTooSmall => "Side length too small",
+            ErrorKind::EvenSideLength => "Side length has to be odd",
+            ErrorKind::BoardTooLarge => "Board too large",
+            ErrorKind::InvalidTileMix => "Tile mix needs at least one tile variant",
+            ErrorKind::WrongTileCount { .. } => {
+                "Tile counts do not add up to the number of movable tiles"
+            }
+            ErrorKind::NoItems => "Board needs at least one item",
+            ErrorKind::TooManyItems { .. } => "Board cannot hold that many items",
+            ErrorKind::OutOfBounds { .. } => "No such row/column exists",
+            ErrorKind::UnMovable { .. } => "Specified row/column is not movable",
+            ErrorKind::UndoMove { .. } => {
+                "Tile cannot be pushed back in where it was previously pushed out"
+            }
+            ErrorKind::PushesOffPlayer { .. } => "Shift would push a player off the board",
+            ErrorKind::InvalidPosition { .. } => "Position is not on the board",
+            ErrorKind::InvalidPlayer { .. } => "No such player exists",
+            ErrorKind::NotYourTurn { .. } => "It is not the player's turn",
+            ErrorKind::UnreachablePosition { .. } => "Position is not reachable by player",
+            ErrorKind::NotMovedYet => "Player has not moved yet",
+            ErrorKind::NoHistory => "Last state in history",
+            ErrorKind::NoRedo => "Nothing to redo",
+            ErrorKind::NoLegalTurn => "No legal turn available",
+            ErrorKind::NoReplay => "Game was not started from settings",
+            ErrorKind::ReplayTooShort => "Replay does not have that many actions",
+            ErrorKind::InvalidGame { .. } => "Game state is inconsistent",
+        })
+    }
+}
+
+impl std::error::Error for Failure {}
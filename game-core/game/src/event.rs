@@ -0,0 +1,102 @@
+use ts_interop::ts_interop;
+
+use crate::{
+    player::{PlayerId, Players, Position},
+    tile::{Item, SideIndex, Tile},
+};
+
+/// Something that changed in a [`Game`] because of an action, so that front
+/// ends and servers can react to it without comparing whole games.
+///
+/// Rotating the free tile emits no event, the new rotation is all there is
+/// to it.
+///
+/// [`Game`]: crate::game::Game
+#[ts_interop]
+#[derive(Clone, Debug)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    /// The free tile was pushed in at the side index and the tile at the
+    /// other end was pushed out, becoming the new free tile.
+    TilesShifted {
+        side_index: SideIndex,
+        pushed_out_tile: Tile,
+        /// The players standing on the shifted row or column.
+        moved_players: Vec<MovedPlayer>,
+    },
+    /// The player walked along the path, start and destination included.
+    PlayerMoved {
+        player_id: PlayerId,
+        path: Vec<Position>,
+    },
+    ItemCollected {
+        player_id: PlayerId,
+        item: Item,
+    },
+    /// It is now the turn of the player.
+    TurnChanged {
+        player_id: PlayerId,
+    },
+    PlayerRemoved {
+        player_id: PlayerId,
+    },
+    /// The player reached the placement in the standings. Without playing to
+    /// the end, only the winners finish.
+    PlayerFinished {
+        player_id: PlayerId,
+        placement: usize,
+    },
+    /// The player ran out of time and was penalized, see
+    /// [`TimeoutPenalty`](crate::clock::TimeoutPenalty).
+    TimedOut {
+        player_id: PlayerId,
+    },
+    GameWon {
+        player_id: PlayerId,
+    },
+}
+
+impl GameEvent {
+    /// Whether the event may be sent to the viewer, `None` being a spectator.
+    /// Which item was collected is only told to the player collecting it
+    /// and their teammates, who share their cards.
+    pub fn is_visible_to(&self, viewer: Option<PlayerId>, players: &Players) -> bool {
+        match self {
+            GameEvent::ItemCollected { player_id, .. } => viewer.is_some_and(|viewer| {
+                viewer == *player_id || players.teammates(viewer).any(|p| p.get_id() == *player_id)
+            }),
+            _ => true,
+        }
+    }
+}
+
+/// A player that was moved along with the tile it stands on.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MovedPlayer {
+    player_id: PlayerId,
+    from: Position,
+    to: Position,
+}
+
+impl MovedPlayer {
+    pub fn new(player_id: PlayerId, from: Position, to: Position) -> Self {
+        Self {
+            player_id,
+            from,
+            to,
+        }
+    }
+
+    pub fn get_player_id(&self) -> PlayerId {
+        self.player_id
+    }
+
+    pub fn get_from(&self) -> Position {
+        self.from
+    }
+
+    pub fn get_to(&self) -> Position {
+        self.to
+    }
+}
@@ -1,9 +1,23 @@
-use std::collections::BTreeSet;
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fmt::{self, Display},
+};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use crate::{
-    board::{Board, NewBoardError, ShiftTileError},
-    player::{MoveError, PlayerId, Players, Position},
+    board::{
+        Board, InvalidBoardError, ItemCount, NewBoardError, ShiftTileError, TileMix, Topology,
+    },
+    clock::{Clock, TimeControl, TimeoutPenalty},
+    event::{GameEvent, MovedPlayer},
+    player::{
+        InvalidPlayersError, MoveError, PlayerId, Players, Position, RemovePlayerError, TeamId,
+    },
     tile::{Rotation, SideIndex},
+    view::GameView,
 };
 use ts_interop::ts_interop;
 
@@ -14,6 +28,25 @@ pub struct Game {
     players: Players,
     phase: GamePhase,
     winner: Option<PlayerId>,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    push_off: PushOffRule,
+    /// Only present in timed games.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    clock: Option<Clock>,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    win_condition: WinCondition,
+    /// Whether the game goes on after the first player finished, until all
+    /// but one player or team have finished.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    play_to_end: bool,
+    /// The players who finished, in the order they did.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    standings: Vec<Standing>,
 }
 
 #[ts_interop]
@@ -23,43 +56,414 @@ pub enum GamePhase {
     MovePlayer,
 }
 
+/// What happens to a player standing on the tile that is pushed out of the
+/// board by a shift.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum PushOffRule {
+    /// The player is put on the tile that was just pushed in on the other
+    /// side of the board, as in the official rules.
+    #[default]
+    Wrap,
+    /// Shifts that would push a player off the board are not allowed.
+    Block,
+}
+
+/// What a player has to do to finish the game. In team mode, the items of
+/// the whole team count.
 #[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(tag = "type")]
+pub enum WinCondition {
+    /// Collect all items, then return to the start position, as in the
+    /// official rules.
+    #[default]
+    ReturnToStart,
+    /// Collect all items, then reach the center of the board.
+    ReachCenter,
+    /// Collect the number of items, without returning anywhere.
+    CollectItems { count: usize },
+    /// Nobody finishes by moving. After the number of rounds, the players
+    /// are placed by the number of items they collected.
+    MostItems { rounds: usize },
+}
+
+/// The result of a player who finished the game.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Standing {
+    player_id: PlayerId,
+    /// Starts at 1 for the winner. Teammates share their placement.
+    placement: usize,
+    /// The turns the player took, including the ones that timed out.
+    turns: usize,
+    /// The number of items the player collected.
+    collected: usize,
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    team: Option<TeamId>,
+}
+
+/// A complete turn of the current player: rotating the free tile, shifting it
+/// into the board and moving to a destination.
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Turn {
+    rotation: Rotation,
+    side_index: SideIndex,
+    destination: Position,
+}
+
+#[ts_interop]
+#[derive(Clone)]
 pub struct GameStartSettings {
     players: BTreeSet<PlayerId>,
-    side_length: usize,
+    width: usize,
+    height: usize,
     items_per_player: usize,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    topology: Topology,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    tile_mix: TileMix,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    item_count: ItemCount,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    push_off: PushOffRule,
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    win_condition: WinCondition,
+    /// Without time control, players can take as long as they want.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    time_control: Option<TimeControl>,
+    /// Groups the players into teams, each player has to be in exactly one
+    /// of them. Without teams, everyone plays for themselves.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    teams: Vec<BTreeSet<PlayerId>>,
+    /// Without playing to the end, the game is over as soon as the first
+    /// player finished.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    play_to_end: bool,
+    /// The same settings with the same seed always produce the same game.
+    /// Without a seed, the game is generated from a random seed.
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    seed: Option<u64>,
 }
 
 #[derive(Debug)]
 pub enum NewGameError {
     BoardError(NewBoardError),
     PlayerError,
+    /// The teams do not split the players into at least two teams.
+    TeamError,
+    /// Nobody could ever meet the win condition.
+    WinConditionError,
+}
+
+/// Why a game that was not started by [`Game::new`], e.g. one that was
+/// deserialized, is inconsistent.
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvalidGameError {
+    BoardError(InvalidBoardError),
+    PlayersError(InvalidPlayersError),
+    /// The game is still running with less than two players or teams.
+    NotEnoughPlayers,
+    /// The winner is neither one of the players nor in the standings.
+    UnknownWinner(PlayerId),
+    /// Some players are in a team and others are not.
+    MixedTeams,
+    /// The player's remaining time does not fit the total time of the time
+    /// control, or there is none.
+    RemainingTime(PlayerId),
+    /// The current turn took longer than the turn limit.
+    TurnTime,
 }
 
-pub type ActionResult<E> = Result<(), GameError<E>>;
+/// The events of a successful action, in the order they happened.
+pub type ActionResult<E> = Result<Vec<GameEvent>, GameError<E>>;
 
+#[derive(Debug)]
 pub enum GameError<T> {
     GameOver,
     StateError,
+    NotYourTurn,
     ActionError(T),
 }
 
 impl Game {
     pub fn new(settings: GameStartSettings) -> Result<Self, NewGameError> {
-        let board = Board::new(settings.side_length)?;
-        let players = Players::new(settings.players, settings.items_per_player, &board)
-            .ok_or(NewGameError::PlayerError)?;
+        let mut rng = match settings.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy(),
+        };
+
+        let reachable = match settings.win_condition {
+            WinCondition::CollectItems { count } => {
+                (1..=settings.items_per_player).contains(&count)
+            }
+            WinCondition::MostItems { rounds } => rounds > 0,
+            WinCondition::ReturnToStart | WinCondition::ReachCenter => true,
+        };
+        if !reachable {
+            return Err(NewGameError::WinConditionError);
+        }
+
+        let board = Board::with_assortment(
+            settings.width,
+            settings.height,
+            settings.tile_mix,
+            settings.item_count,
+            &mut rng,
+        )?
+        .with_topology(settings.topology);
+        let mut players = Players::with_rng(
+            settings.players,
+            settings.items_per_player,
+            &board,
+            &mut rng,
+        )
+        .ok_or(NewGameError::PlayerError)?;
+
+        if !settings.teams.is_empty() {
+            let mut assigned = BTreeSet::new();
+            for (team, members) in settings.teams.iter().enumerate() {
+                for &player_id in members {
+                    if !assigned.insert(player_id) {
+                        return Err(NewGameError::TeamError);
+                    }
+                    let player = players
+                        .iter_mut()
+                        .find(|player| player.get_id() == player_id)
+                        .ok_or(NewGameError::TeamError)?;
+                    player.set_team(Some(team));
+                }
+            }
+            if assigned.len() != players.iter().count() || players.count_sides() < 2 {
+                return Err(NewGameError::TeamError);
+            }
+        }
+
+        let clock = settings.time_control.map(Clock::new);
+        if let Some(time_control) = settings.time_control {
+            for player in players.iter_mut() {
+                player.set_remaining_time(time_control.get_total_time());
+            }
+        }
 
         Ok(Self {
             board,
             players,
             phase: GamePhase::MoveTiles,
             winner: None,
+            push_off: settings.push_off,
+            clock,
+            win_condition: settings.win_condition,
+            play_to_end: settings.play_to_end,
+            standings: Vec::new(),
         })
     }
 
+    pub(crate) fn from_parts(
+        board: Board,
+        players: Players,
+        phase: GamePhase,
+        winner: Option<PlayerId>,
+        push_off: PushOffRule,
+    ) -> Self {
+        let mut game = Self {
+            board,
+            players,
+            phase,
+            winner: None,
+            push_off,
+            clock: None,
+            win_condition: WinCondition::ReturnToStart,
+            play_to_end: false,
+            standings: Vec::new(),
+        };
+        if let Some(winner) = winner.filter(|&id| game.players.get(id).is_some()) {
+            game.place(winner);
+        }
+        game.winner = winner;
+        game
+    }
+
+    /// Makes a game put together by [`Game::from_parts`] play to the end.
+    pub(crate) fn with_play_to_end(self, play_to_end: bool) -> Self {
+        Self {
+            play_to_end,
+            ..self
+        }
+    }
+
+    /// Replaces the standings [`Game::from_parts`] derived from the winner.
+    pub(crate) fn with_standings(self, standings: Vec<Standing>) -> Self {
+        Self { standings, ..self }
+    }
+
+    /// Whether the standings are the ones [`Game::from_parts`] derives from
+    /// the winner, so that they can be left out of the notation.
+    pub(crate) fn has_implied_standings(&self) -> bool {
+        let mut implied = Self {
+            winner: None,
+            standings: Vec::new(),
+            ..self.clone()
+        };
+        if let Some(winner) = self.winner.filter(|&id| self.players.get(id).is_some()) {
+            implied.place(winner);
+        }
+        implied.standings == self.standings
+    }
+
+    /// Changes the win condition of a game put together by
+    /// [`Game::from_parts`].
+    pub(crate) fn with_win_condition(self, win_condition: WinCondition) -> Self {
+        Self {
+            win_condition,
+            ..self
+        }
+    }
+
+    /// Checks that the game is consistent, so that no action on it can panic.
+    /// Games from untrusted sources, like the front end or other peers,
+    /// should be validated before they are played.
+    pub fn validate(&self) -> Result<(), InvalidGameError> {
+        self.board.validate()?;
+        self.players.validate(&self.board)?;
+
+        let in_teams = self
+            .players
+            .iter()
+            .filter(|p| p.get_team().is_some())
+            .count();
+        if in_teams != 0 && in_teams != self.players.iter().count() {
+            return Err(InvalidGameError::MixedTeams);
+        }
+
+        let time_control = self.clock.map(|clock| clock.get_time_control());
+        let total_time = time_control.and_then(|t| t.get_total_time());
+        for player in self.players.iter() {
+            let fits = match (total_time, player.get_remaining_time()) {
+                (Some(total), Some(remaining)) => remaining <= total,
+                (total, remaining) => total.is_none() && remaining.is_none(),
+            };
+            if !fits {
+                return Err(InvalidGameError::RemainingTime(player.get_id()));
+            }
+        }
+        if let Some(clock) = self.clock {
+            let turn_limit = clock.get_time_control().get_turn_limit();
+            if turn_limit.is_some_and(|limit| clock.get_turn_time() > limit) {
+                return Err(InvalidGameError::TurnTime);
+            }
+        }
+
+        match self.winner {
+            Some(winner)
+                if self.players.get(winner).is_none()
+                    && self.standings.iter().all(|s| s.player_id != winner) =>
+            {
+                Err(InvalidGameError::UnknownWinner(winner))
+            }
+            None if self.players.count_sides() < 2 => Err(InvalidGameError::NotEnoughPlayers),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn get_players(&self) -> &Players {
+        &self.players
+    }
+
+    pub fn get_phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    /// The player who finished first. In team mode, their whole team has
+    /// won, see [`Game::get_winning_team`].
+    pub fn get_winner(&self) -> Option<PlayerId> {
+        self.winner
+    }
+
+    pub fn get_winning_team(&self) -> Option<TeamId> {
+        self.standings.first()?.team
+    }
+
+    /// Whether the player has won, alone or with their team.
+    pub fn has_won(&self, player_id: PlayerId) -> bool {
+        self.get_standing(player_id)
+            .is_some_and(|standing| standing.placement == 1)
+    }
+
+    /// The players who finished, in the order they did. Without playing to
+    /// the end, only the winners are placed.
+    pub fn get_standings(&self) -> &[Standing] {
+        &self.standings
+    }
+
+    pub fn get_standing(&self, player_id: PlayerId) -> Option<&Standing> {
+        self.standings.iter().find(|s| s.player_id == player_id)
+    }
+
+    pub fn plays_to_end(&self) -> bool {
+        self.play_to_end
+    }
+
+    /// Whether no more actions can be taken. Without playing to the end, the
+    /// game is over once there is a winner, otherwise once everyone still in
+    /// the game is placed.
+    pub fn is_over(&self) -> bool {
+        self.winner.is_some()
+            && (!self.play_to_end
+                || self
+                    .players
+                    .iter()
+                    .all(|p| self.get_standing(p.get_id()).is_some()))
+    }
+
+    pub fn get_win_condition(&self) -> WinCondition {
+        self.win_condition
+    }
+
+    pub fn get_push_off_rule(&self) -> PushOffRule {
+        self.push_off
+    }
+
+    /// The clock of a timed game.
+    pub fn get_clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// What the player is allowed to see of the game: the item cards of
+    /// their team, but only how many items the others have collected and have
+    /// left.
+    /// Unknown players see what spectators see.
+    pub fn view_for(&self, player_id: PlayerId) -> GameView {
+        GameView::new(self, Some(player_id))
+    }
+
+    /// What spectators are allowed to see of the game, which is no item
+    /// cards at all.
+    pub fn spectator_view(&self) -> GameView {
+        GameView::new(self, None)
+    }
+
+    /// The player whose turn it is, who is the only one allowed to shift and
+    /// move.
+    pub fn current_player_id(&self) -> PlayerId {
+        self.players.current_player().get_id()
+    }
+
     pub fn currently_reachable(&self) -> Option<Vec<Position>> {
-        if self.winner.is_some() {
+        if self.is_over() {
             return None;
         }
 
@@ -69,8 +473,45 @@ impl Game {
         )
     }
 
+    /// Every turn the current player can legally take. Empty if the game is
+    /// over or the tiles have already been shifted this turn.
+    pub fn legal_turns(&self) -> Vec<Turn> {
+        self.iter_legal_turns().collect()
+    }
+
+    /// Lazy version of [`Game::legal_turns`]. The board is only shifted once
+    /// the turns of the previous shift have been consumed.
+    pub fn iter_legal_turns(&self) -> impl Iterator<Item = Turn> + '_ {
+        let can_shift = !self.is_over() && self.phase == GamePhase::MoveTiles;
+
+        Rotation::ALL
+            .into_iter()
+            .filter(move |_| can_shift)
+            .flat_map(|rotation| {
+                self.board
+                    .get_shift_options()
+                    .map(move |side_index| (rotation, side_index))
+            })
+            .flat_map(|(rotation, side_index)| {
+                let mut game = self.clone();
+                game.rotate_free_tile(rotation);
+                let player_id = game.current_player_id();
+                let mut destinations = match game.shift_tiles(player_id, side_index) {
+                    Ok(_) => game.currently_reachable().unwrap_or_default(),
+                    Err(_) => Vec::new(),
+                };
+                destinations.sort();
+
+                destinations.into_iter().map(move |destination| Turn {
+                    rotation,
+                    side_index,
+                    destination,
+                })
+            })
+    }
+
     pub fn rotate_free_tile(&mut self, rotation: Rotation) -> bool {
-        if self.winner.is_some() {
+        if self.is_over() {
             false
         } else {
             self.board.rotate_free_tile(rotation);
@@ -78,65 +519,457 @@ impl Game {
         }
     }
 
-    pub fn shift_tiles(&mut self, side_index: SideIndex) -> ActionResult<ShiftTileError> {
-        if self.winner.is_some() {
+    pub fn shift_tiles(
+        &mut self,
+        player_id: PlayerId,
+        side_index: SideIndex,
+    ) -> ActionResult<ShiftTileError> {
+        if self.is_over() {
             return Err(GameError::GameOver);
         }
 
+        if player_id != self.current_player_id() {
+            return Err(GameError::NotYourTurn);
+        }
+
         if self.phase != GamePhase::MoveTiles {
             return Err(GameError::StateError);
         }
 
+        let pushed_out = self.board.get_pushed_out(side_index)?;
+        if self.push_off == PushOffRule::Block
+            && self.players.iter().any(|p| p.get_position() == pushed_out)
+        {
+            return Err(GameError::ActionError(ShiftTileError::PushesOffPlayer));
+        }
+
         let changes = self.board.shift_tiles(side_index)?;
+        let mut moved_players = Vec::new();
         for player in self.players.iter_mut() {
-            if let Some(new_pos) = changes.get(&player.get_position()) {
-                player.set_position(*new_pos);
+            if let Some(&new_pos) = changes.get(&player.get_position()) {
+                moved_players.push(MovedPlayer::new(
+                    player.get_id(),
+                    player.get_position(),
+                    new_pos,
+                ));
+                player.set_position(new_pos);
             }
         }
         self.phase = GamePhase::MovePlayer;
 
-        Ok(())
+        Ok(vec![GameEvent::TilesShifted {
+            side_index,
+            pushed_out_tile: *self.board.get_free_tile().tile(),
+            moved_players,
+        }])
     }
 
-    pub fn remove_player(&mut self, player_id: PlayerId) -> ActionResult<()> {
-        if self.winner.is_some() {
+    pub fn remove_player(&mut self, player_id: PlayerId) -> ActionResult<RemovePlayerError> {
+        if self.is_over() {
             return Err(GameError::GameOver);
         }
 
-        self.winner = self.players.remove_player(player_id)?;
+        let player_turn = self.current_player_id();
+        let last = self.players.remove_player(player_id)?;
 
-        Ok(())
+        let mut events = vec![GameEvent::PlayerRemoved { player_id }];
+        if let Some(last) = last {
+            events.extend(self.place(last));
+        }
+        if self.is_over() || self.current_player_id() != player_turn {
+            self.start_turn();
+            events.push(self.turn_event());
+        }
+        Ok(events)
     }
 
     pub fn move_player(
         &mut self,
         player_id: PlayerId,
         position: Position,
-    ) -> Result<Vec<Position>, GameError<MoveError>> {
-        if self.winner.is_some() {
+    ) -> ActionResult<MoveError> {
+        if self.is_over() {
             return Err(GameError::GameOver);
         }
 
+        if player_id != self.current_player_id() {
+            return Err(GameError::NotYourTurn);
+        }
+
         if self.phase != GamePhase::MovePlayer {
             return Err(GameError::StateError);
         }
 
-        let result = self.players.move_player(player_id, position, &self.board)?;
+        let result =
+            self.players
+                .move_player(player_id, position, &self.board, self.win_condition)?;
         self.phase = GamePhase::MoveTiles;
-        self.winner = result.winner;
 
-        Ok(result.path)
+        let mut events = vec![GameEvent::PlayerMoved {
+            player_id,
+            path: result.path,
+        }];
+        if let Some(item) = result.collected {
+            events.push(GameEvent::ItemCollected { player_id, item });
+        }
+        match result.winner {
+            Some(finisher) => events.extend(self.finish(finisher)),
+            None => events.extend(self.check_rounds()),
+        }
+        self.start_turn();
+        events.push(self.turn_event());
+        Ok(events)
+    }
+
+    /// Advances the clock of a timed game to the timestamp, in milliseconds,
+    /// and charges the time that passed to the current player. Players who
+    /// run out of time are penalized at the moment their time ran out, so
+    /// it does not matter how often the clock is advanced.
+    ///
+    /// The first call starts the clock. There are at most as many timeouts
+    /// per call as there are players, so that a clock jumping far ahead does
+    /// not run the game on its own. Untimed and finished games ignore the
+    /// clock.
+    pub fn advance_clock(&mut self, now: u64) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        let Some(mut clock) = self.clock.take() else {
+            return events;
+        };
+
+        let mut elapsed = clock.advance(now);
+        let max_timeouts = self.players.iter().count();
+        for timeouts in 0.. {
+            if self.is_over() {
+                break;
+            }
+
+            let player = self.players.current_player_mut();
+            let time_left = clock.get_time_left(player);
+            match time_left {
+                Some(time_left) if elapsed >= time_left && timeouts < max_timeouts => {
+                    clock.charge(player, time_left);
+                    elapsed -= time_left;
+                }
+                _ => {
+                    clock.charge(player, time_left.map_or(elapsed, |t| t.min(elapsed)));
+                    break;
+                }
+            }
+
+            let penalty = clock.get_time_control().get_penalty();
+            events.extend(self.time_out(penalty, now - elapsed));
+            clock.start_turn();
+        }
+
+        self.clock = Some(clock);
+        events
+    }
+
+    /// Penalizes the current player for running out of time at the deadline.
+    fn time_out(&mut self, penalty: TimeoutPenalty, deadline: u64) -> Vec<GameEvent> {
+        let player_id = self.current_player_id();
+        let mut events = vec![GameEvent::TimedOut { player_id }];
+
+        if penalty == TimeoutPenalty::Forfeit {
+            events.extend(self.remove_player(player_id).unwrap_or_default());
+            // The next player starts with a shift, even if the player who
+            // timed out had already shifted. The clock restarts the turn.
+            self.phase = GamePhase::MoveTiles;
+            return events;
+        }
+
+        if penalty == TimeoutPenalty::RandomShift && self.phase == GamePhase::MoveTiles {
+            // Seeded with the deadline, so that replays time out the same way.
+            let mut rng = ChaCha8Rng::seed_from_u64(deadline);
+            let mut options: Vec<_> = self.board.get_shift_options().collect();
+            options.shuffle(&mut rng);
+            if let Some(shifted) = options
+                .into_iter()
+                .find_map(|side_index| self.shift_tiles(player_id, side_index).ok())
+            {
+                events.extend(shifted);
+            }
+        }
+
+        self.phase = GamePhase::MoveTiles;
+        self.players.current_player_mut().count_turn();
+        self.players.next_player_turn();
+        events.extend(self.check_rounds());
+        self.start_turn();
+        events.push(self.turn_event());
+        events
+    }
+
+    /// Places the player who just finished along with their team. When
+    /// playing to the end, they leave the rotation, and once a single side is
+    /// left it is placed last.
+    fn finish(&mut self, player_id: PlayerId) -> Vec<GameEvent> {
+        let side: Vec<_> = self
+            .players
+            .teammates(player_id)
+            .map(|p| p.get_id())
+            .collect();
+        let mut events = self.place(player_id);
+        if !self.play_to_end {
+            return events;
+        }
+
+        for id in side {
+            if let Ok(Some(last)) = self.players.remove_player(id) {
+                events.extend(self.place(last));
+            }
+        }
+        events
+    }
+
+    /// Ends a game limited to a number of rounds once everyone has played
+    /// them, placing the players by the items they and their teams
+    /// collected. Sides with as many items share the placement.
+    fn check_rounds(&mut self) -> Vec<GameEvent> {
+        let WinCondition::MostItems { rounds } = self.win_condition else {
+            return Vec::new();
+        };
+        if self.is_over() || self.players.iter().any(|p| p.get_turns() < rounds) {
+            return Vec::new();
+        }
+
+        let mut sides: Vec<(usize, PlayerId)> = Vec::new();
+        for player in self.players.iter() {
+            let known = sides.iter().any(|&(_, id)| {
+                self.players
+                    .teammates(id)
+                    .any(|p| p.get_id() == player.get_id())
+            });
+            if !known {
+                let collected = self.players.collected_by_team(player.get_id());
+                sides.push((collected, player.get_id()));
+            }
+        }
+        sides.sort_by_key(|&(collected, _)| std::cmp::Reverse(collected));
+
+        let first = self.standings.last().map_or(1, |s| s.placement + 1);
+        let mut events = Vec::new();
+        let mut placement = first;
+        for (i, &(collected, player_id)) in sides.iter().enumerate() {
+            if i > 0 && collected < sides[i - 1].0 {
+                placement = first + i;
+            }
+            events.extend(self.place_at(player_id, placement));
+        }
+        events
+    }
+
+    /// Adds the player and their teammates to the standings, behind everyone
+    /// placed so far.
+    fn place(&mut self, player_id: PlayerId) -> Vec<GameEvent> {
+        let placement = self.standings.last().map_or(1, |s| s.placement + 1);
+        self.place_at(player_id, placement)
+    }
+
+    fn place_at(&mut self, player_id: PlayerId, placement: usize) -> Vec<GameEvent> {
+        self.winner.get_or_insert(player_id);
+        let mut events = Vec::new();
+        for player in self.players.teammates(player_id) {
+            self.standings.push(Standing {
+                player_id: player.get_id(),
+                placement,
+                turns: player.get_turns(),
+                collected: player.get_collected().len(),
+                team: player.get_team(),
+            });
+            events.push(GameEvent::PlayerFinished {
+                player_id: player.get_id(),
+                placement,
+            });
+        }
+        events
+    }
+
+    /// Gives the next player a fresh turn on the clock.
+    fn start_turn(&mut self) {
+        if let Some(clock) = &mut self.clock {
+            clock.start_turn();
+        }
+    }
+
+    /// Whose turn it is after an action, or who won if it ended the game.
+    fn turn_event(&self) -> GameEvent {
+        match self.winner {
+            Some(player_id) if self.is_over() => GameEvent::GameWon { player_id },
+            _ => GameEvent::TurnChanged {
+                player_id: self.current_player_id(),
+            },
+        }
+    }
+}
+
+impl Turn {
+    pub fn new(rotation: Rotation, side_index: SideIndex, destination: Position) -> Self {
+        Self {
+            rotation,
+            side_index,
+            destination,
+        }
+    }
+
+    pub fn get_rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    pub fn get_side_index(&self) -> SideIndex {
+        self.side_index
+    }
+
+    pub fn get_destination(&self) -> Position {
+        self.destination
+    }
+}
+
+impl Standing {
+    pub(crate) fn new(
+        player_id: PlayerId,
+        placement: usize,
+        turns: usize,
+        collected: usize,
+        team: Option<TeamId>,
+    ) -> Self {
+        Self {
+            player_id,
+            placement,
+            turns,
+            collected,
+            team,
+        }
+    }
+
+    pub fn get_player_id(&self) -> PlayerId {
+        self.player_id
+    }
+
+    pub fn get_placement(&self) -> usize {
+        self.placement
+    }
+
+    pub fn get_turns(&self) -> usize {
+        self.turns
+    }
+
+    pub fn get_collected(&self) -> usize {
+        self.collected
+    }
+
+    pub fn get_team(&self) -> Option<TeamId> {
+        self.team
     }
 }
 
 impl GameStartSettings {
+    /// Settings for a square board.
     pub fn new(players: BTreeSet<PlayerId>, side_length: usize, items_per_player: usize) -> Self {
         Self {
             players,
-            side_length,
+            width: side_length,
+            height: side_length,
             items_per_player,
+            topology: Topology::Bounded,
+            tile_mix: TileMix::default(),
+            item_count: ItemCount::Classic,
+            push_off: PushOffRule::Wrap,
+            win_condition: WinCondition::ReturnToStart,
+            time_control: None,
+            teams: Vec::new(),
+            play_to_end: false,
+            seed: None,
+        }
+    }
+
+    /// Changes the board to have `width` columns and `height` rows.
+    pub fn with_size(self, width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            ..self
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    pub fn with_tile_mix(self, tile_mix: TileMix) -> Self {
+        Self { tile_mix, ..self }
+    }
+
+    /// Changes how many items are placed on the board, independently of how
+    /// many each player has to collect.
+    pub fn with_item_count(self, item_count: ItemCount) -> Self {
+        Self { item_count, ..self }
+    }
+
+    pub fn with_push_off_rule(self, push_off: PushOffRule) -> Self {
+        Self { push_off, ..self }
+    }
+
+    pub fn with_win_condition(self, win_condition: WinCondition) -> Self {
+        Self {
+            win_condition,
+            ..self
+        }
+    }
+
+    pub fn with_time_control(self, time_control: TimeControl) -> Self {
+        Self {
+            time_control: Some(time_control),
+            ..self
+        }
+    }
+
+    /// Plays in teams, the team id of each player being the index of its team.
+    pub fn with_teams(self, teams: Vec<BTreeSet<PlayerId>>) -> Self {
+        Self { teams, ..self }
+    }
+
+    /// Keeps playing after the first player finished, to place everyone.
+    pub fn with_play_to_end(self, play_to_end: bool) -> Self {
+        Self {
+            play_to_end,
+            ..self
         }
     }
+
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    /// Chooses a random seed if none is set yet, so that the game started
+    /// from these settings can be reproduced.
+    pub fn seeded(self) -> Self {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        self.with_seed(seed)
+    }
+
+    pub fn get_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn get_players(&self) -> &BTreeSet<PlayerId> {
+        &self.players
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_items_per_player(&self) -> usize {
+        self.items_per_player
+    }
 }
 
 impl From<NewBoardError> for NewGameError {
@@ -145,6 +978,88 @@ impl From<NewBoardError> for NewGameError {
     }
 }
 
+impl Display for NewGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewGameError::BoardError(err) => err.fmt(f),
+            NewGameError::PlayerError => f.write_str("Not enough players"),
+            NewGameError::TeamError => {
+                f.write_str("Every player has to be in exactly one of at least two teams")
+            }
+            NewGameError::WinConditionError => f.write_str("Win condition cannot be met"),
+        }
+    }
+}
+
+impl Error for NewGameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NewGameError::BoardError(err) => Some(err),
+            NewGameError::PlayerError
+            | NewGameError::TeamError
+            | NewGameError::WinConditionError => None,
+        }
+    }
+}
+
+impl From<InvalidBoardError> for InvalidGameError {
+    fn from(value: InvalidBoardError) -> Self {
+        InvalidGameError::BoardError(value)
+    }
+}
+
+impl From<InvalidPlayersError> for InvalidGameError {
+    fn from(value: InvalidPlayersError) -> Self {
+        InvalidGameError::PlayersError(value)
+    }
+}
+
+impl Display for InvalidGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidGameError::BoardError(err) => err.fmt(f),
+            InvalidGameError::PlayersError(err) => err.fmt(f),
+            InvalidGameError::NotEnoughPlayers => f.write_str("Not enough players"),
+            InvalidGameError::UnknownWinner(id) => write!(f, "Winner {} is not a player", id),
+            InvalidGameError::MixedTeams => f.write_str("Players have to be all in teams or none"),
+            InvalidGameError::RemainingTime(id) => {
+                write!(f, "Remaining time of player {} does not fit the clock", id)
+            }
+            InvalidGameError::TurnTime => f.write_str("Turn time exceeds the turn limit"),
+        }
+    }
+}
+
+impl Error for InvalidGameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InvalidGameError::BoardError(err) => Some(err),
+            InvalidGameError::PlayersError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Display> Display for GameError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::GameOver => f.write_str("Game has ended"),
+            GameError::StateError => f.write_str("Action not allowed in this phase of the turn"),
+            GameError::NotYourTurn => f.write_str("It is not the player's turn"),
+            GameError::ActionError(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<T: Error + 'static> Error for GameError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GameError::ActionError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
 impl<E> From<E> for GameError<E> {
     fn from(value: E) -> Self {
         GameError::ActionError(value)
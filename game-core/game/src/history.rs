@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+
+/// Undo and redo history of states.
+///
+/// States are stored as a tree: undoing moves to the parent, redoing to a
+/// child. Pushing a new state after undoing either abandons the undone states
+/// (the default), or keeps them as a separate branch for later analysis.
+pub struct History<T> {
+    /// The nodes in the order they were pushed, the first one being the node
+    /// at index `offset`. Nodes of dropped branches are left empty until they
+    /// reach the front.
+    nodes: VecDeque<Option<Node<T>>>,
+    offset: usize,
+    /// The number of nodes that are not empty.
+    len: usize,
+    current: usize,
+    capacity: usize,
+    keep_branches: bool,
+}
+
+struct Node<T> {
+    state: T,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// The child that redo moves to, which is the one visited last. For the
+    /// ancestors of the current state, this is the child towards it.
+    redo_child: Option<usize>,
+}
+
+impl<T> History<T> {
+    /// Creates a history that keeps at most `capacity` states, dropping the
+    /// oldest ones first, along with the branches that split off from them.
+    pub fn new(initial: T, capacity: usize, keep_branches: bool) -> Self {
+        Self {
+            nodes: VecDeque::from([Some(Node::new(initial, None))]),
+            offset: 0,
+            len: 1,
+            current: 0,
+            capacity: capacity.max(1),
+            keep_branches,
+        }
+    }
+
+    pub fn current(&self) -> &T {
+        &self.node(self.current).state
+    }
+
+    /// Changes the current state in place, without adding a new undo step.
+    pub fn current_mut(&mut self) -> &mut T {
+        &mut self.node_mut(self.current).state
+    }
+
+    /// Makes the state the new current one, as a child of the previous one.
+    pub fn push(&mut self, state: T) {
+        if !self.keep_branches {
+            // Without branches the nodes form a single line, so every node
+            // after the current one has been undone.
+            self.nodes.truncate(self.current - self.offset + 1);
+            self.len = self.nodes.len();
+            self.node_mut(self.current).children.clear();
+        }
+
+        let index = self.offset + self.nodes.len();
+        self.nodes
+            .push_back(Some(Node::new(state, Some(self.current))));
+        self.len += 1;
+
+        let parent = self.node_mut(self.current);
+        parent.children.push(index);
+        parent.redo_child = Some(index);
+        self.current = index;
+
+        if self.len > self.capacity {
+            self.remove_root();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.node(self.current).parent.is_some()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.node(self.current).redo_child.is_some()
+    }
+
+    pub fn undo(&mut self) -> Option<&T> {
+        let parent = self.node(self.current).parent?;
+        self.node_mut(parent).redo_child = Some(self.current);
+        self.current = parent;
+        Some(self.current())
+    }
+
+    pub fn redo(&mut self) -> Option<&T> {
+        self.current = self.node(self.current).redo_child?;
+        Some(self.current())
+    }
+
+    /// The number of branches that can be redone from the current state.
+    pub fn branch_count(&self) -> usize {
+        self.node(self.current).children.len()
+    }
+
+    /// Redoes the given branch, in the order the branches were created.
+    pub fn redo_branch(&mut self, branch: usize) -> Option<&T> {
+        let child = *self.node(self.current).children.get(branch)?;
+        self.node_mut(self.current).redo_child = Some(child);
+        self.redo()
+    }
+
+    fn node(&self, index: usize) -> &Node<T> {
+        self.nodes[index - self.offset].as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut Node<T> {
+        self.nodes[index - self.offset].as_mut().unwrap()
+    }
+
+    /// Drops the oldest state, which is the root, and every branch that
+    /// splits off from it. Its child towards the current state becomes the
+    /// new root.
+    fn remove_root(&mut self) {
+        let root = self.nodes.pop_front().flatten().unwrap();
+        self.offset += 1;
+        self.len -= 1;
+
+        // The current state was just pushed, so it is not the root.
+        let new_root = root.redo_child.unwrap();
+        self.node_mut(new_root).parent = None;
+        let mut to_remove: Vec<_> = root
+            .children
+            .into_iter()
+            .filter(|&c| c != new_root)
+            .collect();
+        while let Some(index) = to_remove.pop() {
+            let node = self.nodes[index - self.offset].take().unwrap();
+            self.len -= 1;
+            to_remove.extend(node.children);
+        }
+
+        while self.nodes.front().is_some_and(Option::is_none) {
+            self.nodes.pop_front();
+            self.offset += 1;
+        }
+    }
+}
+
+impl<T> Node<T> {
+    fn new(state: T, parent: Option<usize>) -> Self {
+        Self {
+            state,
+            parent,
+            children: Vec::new(),
+            redo_child: None,
+        }
+    }
+}
@@ -1,19 +1,45 @@
 pub mod board;
+pub mod clock;
+mod connectivity;
+pub mod error;
+pub mod event;
 pub mod game;
+pub mod history;
+pub mod notation;
 pub mod player;
+#[cfg(test)]
+mod properties;
+pub mod replay;
 pub mod tile;
+pub mod view;
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
     use crate::{
-        board::{Board, NewBoardError},
-        game::{Game, GameStartSettings, NewGameError},
-        player::{Players, Position},
-        tile::{Side, SideIndex},
+        board::{
+            Board, InvalidBoardError, ItemCount, NewBoardError, ShiftTileError, TileMix, Topology,
+        },
+        clock::{TimeControl, TimeoutPenalty},
+        error::{ActionKind, ErrorKind, Failure, InvalidGameReason},
+        event::{GameEvent, MovedPlayer},
+        game::{
+            Game, GameError, GamePhase, GameStartSettings, InvalidGameError, NewGameError,
+            PushOffRule, WinCondition,
+        },
+        history::History,
+        notation::{format_game, format_turn, parse_game, parse_turn, NotationError},
+        player::{InvalidPlayersError, Players, Position, RemovePlayerError},
+        replay::{Action, Replay},
+        tile::{Rotation, Side, SideIndex, Tile, TileVariant},
     };
 
     fn new_board() -> Result<Board, NewBoardError> {
-        Board::new(7)
+        Board::new(7, 7)
     }
 
     fn new_players() -> Option<Players> {
@@ -40,10 +66,58 @@ mod tests {
     #[test]
     fn big_boards() {
         for i in 4..30 {
-            assert!(Board::new(2 * i + 1).is_ok());
+            assert!(Board::new(2 * i + 1, 2 * i + 1).is_ok());
         }
     }
 
+    #[test]
+    fn rectangular_boards() {
+        assert!(matches!(Board::new(9, 4), Err(NewBoardError::EvenLength)));
+        assert!(matches!(Board::new(1, 7), Err(NewBoardError::TooSmall)));
+        assert!(matches!(Board::new(3, 3), Err(NewBoardError::TooSmall)));
+        assert!(matches!(
+            Board::new(usize::MAX, 3),
+            Err(NewBoardError::TooLarge)
+        ));
+
+        for (width, height) in [(9, 5), (11, 7), (3, 9)] {
+            let mut board = Board::new(width, height).unwrap();
+            assert_eq!(board.get_width(), width);
+            assert_eq!(board.get_height(), height);
+            assert!(board
+                .get_tile(Position::new(width - 1, height - 1))
+                .is_some());
+            assert!(board.get_tile(Position::new(width, 0)).is_none());
+            assert!(board.get_tile(Position::new(0, height)).is_none());
+
+            let options: Vec<_> = board.get_shift_options().collect();
+            assert_eq!(options.len(), width / 2 * 2 + height / 2 * 2);
+            for side_index in options {
+                assert!(board.shift_tiles(side_index).is_ok());
+            }
+            assert!(matches!(
+                board.shift_tiles(SideIndex::new(Side::Left, height)),
+                Err(ShiftTileError::OutOfBounds)
+            ));
+        }
+
+        let settings = GameStartSettings::new([0, 1, 2, 3].into(), 9, 6).with_size(9, 5);
+        let game = Game::new(settings).unwrap();
+        let corners: Vec<_> = game
+            .get_players()
+            .iter()
+            .map(|player| player.get_start_position())
+            .collect();
+        assert_eq!(
+            corners,
+            [(0, 0), (8, 0), (8, 4), (0, 4)].map(|(x, y)| Position::new(x, y))
+        );
+        assert!(!game.legal_turns().is_empty());
+
+        let notation = format_game(&game);
+        assert_eq!(format_game(&parse_game(&notation).unwrap()), notation);
+    }
+
     #[test]
     fn new_players_ok() {
         assert!(new_players().is_some());
@@ -52,6 +126,16 @@ mod tests {
     #[test]
     fn remove_player() {
         assert!(new_players().unwrap().remove_player(0).is_ok());
+
+        let mut players = new_players().unwrap();
+        for player_id in 0..3 {
+            assert!(players.remove_player(player_id).is_ok());
+        }
+        assert_eq!(players.current_player().get_id(), 3);
+        assert!(matches!(
+            players.remove_player(3),
+            Err(RemovePlayerError::LastPlayer)
+        ));
     }
 
     #[test]
@@ -59,7 +143,12 @@ mod tests {
         let new_pos = Position::new(0, 0);
         assert!(new_players()
             .unwrap()
-            .move_player(0, new_pos, &new_board().unwrap())
+            .move_player(
+                0,
+                new_pos,
+                &new_board().unwrap(),
+                WinCondition::ReturnToStart
+            )
             .is_ok());
     }
 
@@ -68,10 +157,1010 @@ mod tests {
         assert!(new_game().is_ok());
     }
 
+    #[test]
+    fn seeded_game_is_deterministic() {
+        let new_seeded_game =
+            || Game::new(GameStartSettings::new([0, 1, 2, 3].into(), 7, 6).with_seed(42));
+        let a = serde_json::to_string(&new_seeded_game().unwrap()).unwrap();
+        let b = serde_json::to_string(&new_seeded_game().unwrap()).unwrap();
+        assert_eq!(a, b);
+
+        let other = Game::new(GameStartSettings::new([0, 1, 2, 3].into(), 7, 6).with_seed(43));
+        assert_ne!(a, serde_json::to_string(&other.unwrap()).unwrap());
+    }
+
+    #[test]
+    fn legal_turns_are_playable() {
+        let game = new_game().unwrap();
+        let turns = game.legal_turns();
+        assert!(!turns.is_empty());
+
+        for turn in turns {
+            let mut game = game.clone();
+            assert!(game.rotate_free_tile(turn.get_rotation()));
+            assert!(game.shift_tiles(0, turn.get_side_index()).is_ok());
+            assert!(game.move_player(0, turn.get_destination()).is_ok());
+        }
+    }
+
+    #[test]
+    fn legal_turns_respect_rules() {
+        let mut game = new_game().unwrap();
+        let shift = SideIndex::new(Side::Top, 1);
+        assert!(game.shift_tiles(0, shift).is_ok());
+        assert!(game.legal_turns().is_empty());
+
+        assert!(game.move_player(0, Position::new(0, 0)).is_ok());
+        let turns = game.legal_turns();
+        assert!(turns
+            .iter()
+            .all(|turn| turn.get_side_index() != shift.shift()));
+        assert!(turns
+            .iter()
+            .all(|turn| turn.get_side_index().get_index() % 2 == 1));
+    }
+
+    #[test]
+    fn structured_errors() {
+        let mut game = new_game().unwrap();
+        let side_index = SideIndex::new(Side::Left, 2);
+        let err = game.shift_tiles(0, side_index).unwrap_err();
+        let failure = Failure::shift_tiles(err, 0, side_index);
+
+        assert_eq!(failure.get_action(), ActionKind::ShiftTiles);
+        assert_eq!(failure.get_kind(), ErrorKind::UnMovable { side_index });
+        assert_eq!(
+            failure.to_string(),
+            "Cannot shift tiles: Specified row/column is not movable"
+        );
+
+        let err = Game::new(GameStartSettings::new([0].into(), 7, 6)).err();
+        assert_eq!(err.unwrap().to_string(), "Not enough players");
+    }
+
+    #[test]
+    fn replay_rebuilds_games() {
+        let mut replay = Replay::new(GameStartSettings::new([0, 1].into(), 7, 6));
+        let mut game = replay.initial_game().unwrap();
+        let mut states = vec![serde_json::to_string(&game).unwrap()];
+
+        let actions = [
+            Action::RotateFreeTile {
+                rotation: Rotation::Ninety,
+            },
+            Action::ShiftTiles {
+                player_id: 0,
+                side_index: SideIndex::new(Side::Left, 3),
+            },
+            Action::MovePlayer {
+                player_id: 0,
+                position: Position::new(0, 0),
+            },
+            Action::RemovePlayer { player_id: 1 },
+        ];
+        for action in actions {
+            assert!(replay.apply(&mut game, action).is_ok());
+            states.push(serde_json::to_string(&game).unwrap());
+        }
+        assert!(replay
+            .apply(&mut game, Action::RemovePlayer { player_id: 0 })
+            .is_err());
+        assert_eq!(replay.len(), actions.len());
+
+        let stored = serde_json::to_string(&replay).unwrap();
+        let replay: Replay = serde_json::from_str(&stored).unwrap();
+        for (i, state) in states.iter().enumerate() {
+            let rebuilt = replay.game_at(i).unwrap();
+            assert_eq!(&serde_json::to_string(&rebuilt).unwrap(), state);
+        }
+        assert!(replay.game_at(actions.len() + 1).is_err());
+    }
+
+    #[test]
+    fn history_undo_redo() {
+        let mut history = History::new(0, 3, false);
+        history.push(1);
+        history.push(2);
+        history.push(3);
+        assert_eq!(history.undo(), Some(&2));
+        assert_eq!(history.undo(), Some(&1));
+        assert!(!history.can_undo());
+        assert_eq!(history.redo(), Some(&2));
+
+        history.push(4);
+        assert!(!history.can_redo());
+        assert_eq!(history.undo(), Some(&2));
+        assert_eq!(history.branch_count(), 1);
+        assert_eq!(history.redo(), Some(&4));
+    }
+
+    #[test]
+    fn history_branches() {
+        let mut history = History::new(0, 4, true);
+        history.push(1);
+        history.undo();
+        history.push(2);
+        assert_eq!(history.undo(), Some(&0));
+        assert_eq!(history.branch_count(), 2);
+        assert_eq!(history.redo(), Some(&2));
+        history.undo();
+        assert_eq!(history.redo_branch(0), Some(&1));
+        assert!(history.redo_branch(1).is_none());
+
+        // Dropping the oldest state drops the branches that split off from it.
+        history.push(3);
+        history.push(4);
+        assert_eq!(history.undo(), Some(&3));
+        assert_eq!(history.undo(), Some(&1));
+        assert!(!history.can_undo());
+        assert_eq!(history.branch_count(), 1);
+        history.push(5);
+        assert_eq!(history.undo(), Some(&1));
+        assert_eq!(history.branch_count(), 2);
+        assert_eq!(history.redo_branch(1), Some(&5));
+        history.push(6);
+        assert_eq!(history.undo(), Some(&5));
+        assert!(!history.can_undo());
+        assert_eq!(history.branch_count(), 1);
+    }
+
+    #[test]
+    fn notation_round_trip() {
+        let mut game = new_game().unwrap();
+        assert!(game.shift_tiles(0, SideIndex::new(Side::Bottom, 3)).is_ok());
+
+        let notation = format_game(&game);
+        let parsed = parse_game(&notation).unwrap();
+        assert_eq!(format_game(&parsed), notation);
+
+        for turn in parsed.legal_turns().into_iter().take(10) {
+            assert!(parse_turn(&format_turn(&turn)) == Ok(turn));
+        }
+        for turn in new_game().unwrap().legal_turns() {
+            assert!(parse_turn(&format_turn(&turn)) == Ok(turn));
+        }
+    }
+
+    #[test]
+    fn notation_examples() {
+        let notation = "L1T0L2/I0T31I1/L0T2L3 I1@B1 0:0,0:0,0::1/1:2,0:2,0:: 1 M -";
+        let game = parse_game(notation).unwrap();
+        assert_eq!(format_game(&game), notation);
+        assert_eq!(game.current_player_id(), 1);
+
+        assert_eq!(
+            parse_game("L1T0/I0 I1 0:0,0:0,0:: 0 S -").err(),
+            Some(NotationError::InvalidBoardShape)
+        );
+        assert_eq!(
+            parse_game("L1T0L2/I0T31I1/L0T2L3 I1 0:5,0:0,0:: 0 S -").err(),
+            Some(NotationError::InvalidPosition("5,0".into()))
+        );
+        assert_eq!(
+            parse_game("L1T0L2/I0T31I1/L0T2L3 I1 0:0,0:0,0:: 3 S -").err(),
+            Some(NotationError::UnknownPlayer(3))
+        );
+        assert_eq!(
+            parse_game("L1T0L2/I0T31I1/L0T2L3 I1 0:0,0:0,0::/0:2,0:2,0:: 0 S -").err(),
+            Some(NotationError::DuplicatePlayer(0))
+        );
+
+        let turn = parse_turn("R90 T3 (4,2)").unwrap();
+        assert_eq!(turn.get_side_index(), SideIndex::new(Side::Top, 3));
+        assert_eq!(turn.get_destination(), Position::new(4, 2));
+        assert_eq!(format_turn(&turn), "R90 T3 (4,2)");
+        assert!(parse_turn("R45 T3 (4,2)").is_err());
+    }
+
+    /// The plain breadth-first search the bitset flood fill replaced.
+    fn reference_reachable(board: &Board, start: Position) -> BTreeSet<Position> {
+        // Bridges are walked straight across, so the side a tile was entered
+        // at is part of the state.
+        let mut reached = BTreeSet::from([(start, 0)]);
+        let mut to_visit = vec![(start, None)];
+        while let Some((position, entered_at)) = to_visit.pop() {
+            let exits = board[position].get_exit_mask(entered_at);
+            for side in Side::ALL
+                .into_iter()
+                .filter(|side| exits & side.mask() != 0)
+            {
+                let Some(neighbour) = board.get_neighbour(position, side) else {
+                    continue;
+                };
+                let entered_at = side.opposite();
+                let connected = board[neighbour].get_connection_mask() & entered_at.mask() != 0;
+                if connected && reached.insert((neighbour, entered_at.mask())) {
+                    to_visit.push((neighbour, Some(entered_at)));
+                }
+            }
+        }
+        reached.into_iter().map(|(position, _)| position).collect()
+    }
+
+    #[test]
+    fn connectivity_matches_reference() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let bridges = TileMix::default().with_bridge(5);
+        for topology in [Topology::Bounded, Topology::Torus] {
+            for (width, height) in [(3, 5), (7, 7), (9, 5), (15, 15), (61, 61)] {
+                for tile_mix in [TileMix::default(), bridges] {
+                    let board = Board::with_tile_mix(width, height, tile_mix, &mut rng)
+                        .unwrap()
+                        .with_topology(topology);
+                    check_connectivity(&board);
+                }
+            }
+        }
+    }
+
+    fn check_connectivity(board: &Board) {
+        let (width, height) = (board.get_width(), board.get_height());
+        for start in [(0, 0), (width / 2, height / 2), (width - 1, height - 1)] {
+            let start = Position::new(start.0, start.1);
+            let reachable = board.get_reachable(start);
+            let expected = reference_reachable(board, start);
+            assert!(reachable.is_sorted_by_key(|p| (p.get_y(), p.get_x())));
+            assert_eq!(reachable.iter().copied().collect::<BTreeSet<_>>(), expected);
+
+            for &goal in &reachable {
+                let path = board.get_path(start, goal).unwrap();
+                assert_eq!((path[0], path[path.len() - 1]), (start, goal));
+                let mut entered_at = None;
+                for step in path.windows(2) {
+                    let side = Side::ALL.into_iter().find(|side| {
+                        board.get_neighbour(step[0], *side) == Some(step[1])
+                            && board[step[0]].get_exit_mask(entered_at) & side.mask() != 0
+                            && board[step[1]].get_connection_mask() & side.opposite().mask() != 0
+                    });
+                    assert!(
+                        side.is_some(),
+                        "{:?} -> {:?} is not connected",
+                        step[0],
+                        step[1]
+                    );
+                    entered_at = side.map(|side| side.opposite());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn game_events() {
+        let notation = "L1T01L2/I0I0I0/L0T2L3 I1 0:1,0:0,0::1/1:2,2:2,2:: 0 S -";
+        let mut game = parse_game(notation).unwrap();
+
+        let top = SideIndex::new(Side::Top, 1);
+        let events = game.shift_tiles(0, top).unwrap();
+        let [GameEvent::TilesShifted {
+            side_index,
+            pushed_out_tile,
+            moved_players,
+        }] = events.as_slice()
+        else {
+            panic!("expected the tiles to be shifted");
+        };
+        assert_eq!(*side_index, top);
+        assert_eq!(pushed_out_tile.get_id(), 7);
+        assert_eq!(
+            moved_players,
+            &[MovedPlayer::new(
+                0,
+                Position::new(1, 0),
+                Position::new(1, 1)
+            )]
+        );
+
+        let destination = Position::new(1, 1);
+        let events = game.move_player(0, destination).unwrap();
+        assert!(matches!(
+            events.as_slice(),
+            [
+                GameEvent::PlayerMoved { player_id: 0, path },
+                GameEvent::ItemCollected { player_id: 0, item },
+                GameEvent::TurnChanged { player_id: 1 },
+            ] if path == &[destination] && item.get_id() == 1
+        ));
+
+        let events = game.remove_player(1).unwrap();
+        assert!(matches!(
+            events.as_slice(),
+            [
+                GameEvent::PlayerRemoved { player_id: 1 },
+                GameEvent::PlayerFinished {
+                    player_id: 0,
+                    placement: 1
+                },
+                GameEvent::GameWon { player_id: 0 },
+            ]
+        ));
+    }
+
+    #[test]
+    fn player_views() {
+        let settings = GameStartSettings::new([0, 1].into(), 7, 3).with_seed(1);
+        let game = Game::new(settings).unwrap();
+        let players = game.get_players();
+
+        let view = game.view_for(0);
+        assert_eq!(view.get_viewer(), Some(0));
+        assert_eq!(view.get_player_turn(), game.current_player_id());
+        let own = view.get_player(0).unwrap();
+        assert_eq!(
+            own.get_to_collect(),
+            Some(players.get(0).unwrap().get_to_collect())
+        );
+        assert_eq!(
+            own.get_next_to_collect(),
+            players.get(0).unwrap().get_next_to_collect()
+        );
+        let opponent = view.get_player(1).unwrap();
+        assert_eq!(opponent.get_to_collect(), None);
+        assert_eq!(opponent.get_collected(), None);
+        assert_eq!(opponent.get_next_to_collect(), None);
+        assert_eq!(opponent.get_to_collect_count(), 3);
+        assert_eq!(opponent.get_collected_count(), 0);
+
+        let spectator = game.spectator_view();
+        assert_eq!(spectator.get_viewer(), None);
+        assert_eq!(spectator.iter_players().count(), 2);
+        assert!(spectator
+            .iter_players()
+            .all(|p| p.get_to_collect().is_none()));
+
+        let json = serde_json::to_string(&game.view_for(1)).unwrap();
+        let hidden = players.get(0).unwrap().get_to_collect();
+        let own = players.get(1).unwrap().get_to_collect();
+        assert!(!json.contains(&serde_json::to_string(hidden).unwrap()));
+        assert!(json.contains(&serde_json::to_string(own).unwrap()));
+
+        let collected = GameEvent::ItemCollected {
+            player_id: 0,
+            item: players.get(0).unwrap().get_next_to_collect().unwrap(),
+        };
+        assert!(collected.is_visible_to(Some(0), players));
+        assert!(!collected.is_visible_to(Some(1), players));
+        assert!(!collected.is_visible_to(None, players));
+        assert!(GameEvent::TurnChanged { player_id: 0 }.is_visible_to(None, players));
+    }
+
+    #[test]
+    fn team_mode() {
+        let settings = |teams: Vec<BTreeSet<usize>>| {
+            GameStartSettings::new([0, 1, 2, 3].into(), 7, 3).with_teams(teams)
+        };
+        let game = Game::new(settings(vec![[0, 2].into(), [1, 3].into()])).unwrap();
+        let teams: Vec<_> = game.get_players().iter().map(|p| p.get_team()).collect();
+        assert_eq!(teams, [Some(0), Some(1), Some(0), Some(1)]);
+        let view = game.view_for(0);
+        assert!(view.get_player(2).unwrap().get_to_collect().is_some());
+        assert!(view.get_player(1).unwrap().get_to_collect().is_none());
+        let collected = GameEvent::ItemCollected {
+            player_id: 0,
+            item: game
+                .get_players()
+                .get(0)
+                .unwrap()
+                .get_next_to_collect()
+                .unwrap(),
+        };
+        assert!(collected.is_visible_to(Some(2), game.get_players()));
+        assert!(!collected.is_visible_to(Some(1), game.get_players()));
+
+        for teams in [
+            vec![[0, 1].into(), [1, 2, 3].into()],
+            vec![[0, 1].into(), [2].into()],
+            vec![[0, 1, 2, 3].into()],
+            vec![[0, 1].into(), [2, 3, 4].into()],
+        ] {
+            assert!(matches!(
+                Game::new(settings(teams)),
+                Err(NewGameError::TeamError)
+            ));
+        }
+
+        // Player 0 has nothing left to collect, its teammate 1 still needs
+        // item 1, which player 0 can reach.
+        let notation =
+            "L1T01L2/I0I0I0/L0T2L3 I1 0:1,0:0,0:::0:0/1:2,2:2,2::1:0:0/2:0,2:0,2::1:0:1 0 S -";
+        let mut game = parse_game(notation).unwrap();
+        assert_eq!(format_game(&game), notation);
+        let teams: Vec<_> = game.get_players().iter().map(|p| p.get_team()).collect();
+        assert_eq!(teams, [Some(0), Some(0), Some(1)]);
+
+        assert!(game.shift_tiles(0, SideIndex::new(Side::Top, 1)).is_ok());
+        let events = game.move_player(0, Position::new(1, 1)).unwrap();
+        assert!(matches!(
+            events[1],
+            GameEvent::ItemCollected { player_id: 0, item } if item.get_id() == 1
+        ));
+        let teammate = game.get_players().get(1).unwrap();
+        assert!(teammate.get_to_collect().is_empty());
+        assert_eq!(teammate.get_collected().len(), 1);
+
+        assert!(game.shift_tiles(1, SideIndex::new(Side::Left, 1)).is_ok());
+        assert!(game.move_player(1, Position::new(2, 2)).is_ok());
+        assert_eq!(game.get_winner(), Some(1));
+        assert_eq!(game.get_winning_team(), Some(0));
+        assert!(game.has_won(0));
+        assert!(!game.has_won(2));
+    }
+
+    #[test]
+    fn play_to_the_end() {
+        // Players 0 and 1 finish as soon as they move, player 2 is last.
+        let notation = "L1T01L2/I0I0I0/L0T2L3 I1 0:0,0:0,0::/1:2,2:2,2::/2:0,2:0,2::1 0 S - end";
+        let mut game = parse_game(notation).unwrap();
+        assert!(game.plays_to_end());
+        assert_eq!(format_game(&game), notation);
+
+        assert!(game.shift_tiles(0, SideIndex::new(Side::Top, 1)).is_ok());
+        let events = game.move_player(0, Position::new(0, 0)).unwrap();
+        assert!(matches!(
+            events[1..],
+            [
+                GameEvent::PlayerFinished {
+                    player_id: 0,
+                    placement: 1
+                },
+                GameEvent::TurnChanged { player_id: 1 },
+            ]
+        ));
+        assert_eq!(game.get_winner(), Some(0));
+        assert!(!game.is_over());
+        assert!(game.get_players().get(0).is_none());
+        assert_eq!(game.validate(), Ok(()));
+
+        // The finished player is only left in the standings.
+        let notation = format_game(&game);
+        assert!(notation.ends_with(" 1 S 0 end placed0:1:1:0"));
+        let parsed = parse_game(&notation).unwrap();
+        assert_eq!(parsed.get_standings(), game.get_standings());
+        assert!(!parsed.is_over());
+        assert_eq!(format_game(&parsed), notation);
+
+        assert!(game.shift_tiles(1, SideIndex::new(Side::Left, 1)).is_ok());
+        let events = game.move_player(1, Position::new(2, 2)).unwrap();
+        assert!(matches!(
+            events[1..],
+            [
+                GameEvent::PlayerFinished {
+                    player_id: 1,
+                    placement: 2
+                },
+                GameEvent::PlayerFinished {
+                    player_id: 2,
+                    placement: 3
+                },
+                GameEvent::GameWon { player_id: 0 },
+            ]
+        ));
+        assert!(game.is_over());
+        assert!(game.has_won(0) && !game.has_won(1));
+        assert_eq!(game.validate(), Ok(()));
+
+        let standings: Vec<_> = game
+            .get_standings()
+            .iter()
+            .map(|s| (s.get_player_id(), s.get_placement(), s.get_turns()))
+            .collect();
+        assert_eq!(standings, [(0, 1, 1), (1, 2, 1), (2, 3, 0)]);
+        assert!(matches!(
+            game.shift_tiles(2, SideIndex::new(Side::Top, 1)),
+            Err(GameError::GameOver)
+        ));
+    }
+
+    #[test]
+    fn win_conditions() {
+        let settings = |win_condition| {
+            GameStartSettings::new([0, 1].into(), 7, 3).with_win_condition(win_condition)
+        };
+        for win_condition in [
+            WinCondition::CollectItems { count: 0 },
+            WinCondition::CollectItems { count: 4 },
+            WinCondition::MostItems { rounds: 0 },
+        ] {
+            assert!(matches!(
+                Game::new(settings(win_condition)),
+                Err(NewGameError::WinConditionError)
+            ));
+        }
+        let game = Game::new(settings(WinCondition::CollectItems { count: 3 })).unwrap();
+        assert_eq!(
+            game.get_win_condition(),
+            WinCondition::CollectItems { count: 3 }
+        );
+
+        let with_condition = |notation: &str, field: &str, win_condition| {
+            let notation = format!("{} {}", notation, field);
+            let game = parse_game(&notation).unwrap();
+            assert_eq!(game.get_win_condition(), win_condition);
+            assert_eq!(format_game(&game), notation);
+            game
+        };
+        // The top shift carries player 0 to the center, onto item 1.
+        let top = SideIndex::new(Side::Top, 1);
+        let center = Position::new(1, 1);
+
+        let notation = "L1T01L2/I0I0I0/L0T2L3 I1 0:1,0:0,0::/1:2,2:2,2:: 0 S -";
+        let mut game = with_condition(notation, "center", WinCondition::ReachCenter);
+        assert!(game.shift_tiles(0, top).is_ok());
+        assert!(game.move_player(0, center).is_ok());
+        assert_eq!(game.get_winner(), Some(0));
+
+        let notation = "L1T01L2/I0I0I0/L0T2L3 I1 0:1,0:0,0::1/1:2,2:2,2:: 0 S -";
+        let mut game = with_condition(
+            notation,
+            "collect1",
+            WinCondition::CollectItems { count: 1 },
+        );
+        assert!(game.shift_tiles(0, top).is_ok());
+        assert!(game.move_player(0, center).is_ok());
+        assert_eq!(game.get_winner(), Some(0));
+
+        let mut game = with_condition(notation, "rounds1", WinCondition::MostItems { rounds: 1 });
+        assert!(game.shift_tiles(0, top).is_ok());
+        assert!(game.move_player(0, center).is_ok());
+        assert!(!game.is_over());
+        // The turns taken count towards the rounds after a round trip, too.
+        let notation = format_game(&game);
+        assert!(notation.contains(":1/1:"));
+        let mut game = parse_game(&notation).unwrap();
+        assert_eq!(format_game(&game), notation);
+        assert!(game.shift_tiles(1, SideIndex::new(Side::Left, 1)).is_ok());
+        let events = game.move_player(1, Position::new(2, 2)).unwrap();
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::GameWon { player_id: 0 })
+        ));
+        assert!(game.is_over());
+        let standings: Vec<_> = game
+            .get_standings()
+            .iter()
+            .map(|s| (s.get_player_id(), s.get_placement(), s.get_collected()))
+            .collect();
+        assert_eq!(standings, [(0, 1, 1), (1, 2, 0)]);
+    }
+
+    #[test]
+    fn push_off_rules() {
+        let notation = "L1I0L2/I0I0I0/L0I0L3 I1 0:1,2:0,0::/1:2,2:2,2:: 0 S -";
+        let (top, bottom) = (
+            SideIndex::new(Side::Top, 1),
+            SideIndex::new(Side::Bottom, 1),
+        );
+        let position = |game: &Game| game.get_players().get(0).unwrap().get_position();
+
+        let mut wrap = parse_game(notation).unwrap();
+        assert_eq!(wrap.get_push_off_rule(), PushOffRule::Wrap);
+        assert!(wrap.shift_tiles(0, top).is_ok());
+        assert_eq!(position(&wrap), Position::new(1, 0));
+
+        let notation = format!("{} block", notation);
+        let mut block = parse_game(&notation).unwrap();
+        assert_eq!(format_game(&block), notation);
+        assert!(matches!(
+            block.shift_tiles(0, top),
+            Err(GameError::ActionError(ShiftTileError::PushesOffPlayer))
+        ));
+        assert_eq!(position(&block), Position::new(1, 2));
+        assert!(block.shift_tiles(0, bottom).is_ok());
+        assert_eq!(position(&block), Position::new(1, 1));
+
+        let settings =
+            GameStartSettings::new([0, 1].into(), 7, 6).with_push_off_rule(PushOffRule::Block);
+        let game = Game::new(settings).unwrap();
+        assert_eq!(game.get_push_off_rule(), PushOffRule::Block);
+    }
+
+    fn timed_game(players: usize, time_control: TimeControl) -> Game {
+        let settings = GameStartSettings::new((0..players).collect(), 7, 6)
+            .with_time_control(time_control)
+            .with_seed(0);
+        let mut game = Game::new(settings).unwrap();
+        assert!(game.advance_clock(0).is_empty());
+        game
+    }
+
+    #[test]
+    fn turn_time_limit() {
+        let mut game = timed_game(
+            2,
+            TimeControl::new(Some(1000), None, TimeoutPenalty::SkipMove),
+        );
+        assert!(game.advance_clock(400).is_empty());
+        assert!(game.shift_tiles(0, SideIndex::new(Side::Top, 1)).is_ok());
+        assert!(game.advance_clock(900).is_empty());
+
+        let board = format_game(&game).split(' ').next().unwrap().to_owned();
+        assert!(matches!(
+            game.advance_clock(1200).as_slice(),
+            [
+                GameEvent::TimedOut { player_id: 0 },
+                GameEvent::TurnChanged { player_id: 1 },
+            ]
+        ));
+        assert!(format_game(&game).starts_with(&board));
+        assert!(game.get_phase() == GamePhase::MoveTiles);
+        assert_eq!(game.get_clock().unwrap().get_turn_time(), 200);
+
+        // Clocks that are advanced rarely catch up on every timeout.
+        assert_eq!(game.advance_clock(3300).len(), 4);
+        assert_eq!(game.current_player_id(), 1);
+        assert_eq!(game.get_clock().unwrap().get_turn_time(), 300);
+
+        // The clock never goes back.
+        assert!(game.advance_clock(0).is_empty());
+        assert_eq!(game.get_clock().unwrap().get_now(), Some(3300));
+    }
+
+    #[test]
+    fn total_time_forfeit() {
+        let mut game = timed_game(
+            4,
+            TimeControl::new(None, Some(1000), TimeoutPenalty::Forfeit),
+        );
+        game.advance_clock(400);
+        assert!(game.shift_tiles(0, SideIndex::new(Side::Top, 1)).is_ok());
+        assert!(game.move_player(0, Position::new(0, 0)).is_ok());
+        let remaining = |game: &Game, id| game.get_players().get(id).unwrap().get_remaining_time();
+        assert_eq!(remaining(&game, 0), Some(600));
+
+        assert!(matches!(
+            game.advance_clock(1400).as_slice(),
+            [
+                GameEvent::TimedOut { player_id: 1 },
+                GameEvent::PlayerRemoved { player_id: 1 },
+                GameEvent::TurnChanged { player_id: 2 },
+            ]
+        ));
+        assert!(game.get_players().get(1).is_none());
+        assert_eq!(remaining(&game, 0), Some(600));
+        assert_eq!(remaining(&game, 2), Some(1000));
+
+        // Running out of time after shifting hands the next player a fresh
+        // turn.
+        assert!(game.shift_tiles(2, SideIndex::new(Side::Top, 3)).is_ok());
+        assert!(matches!(
+            game.advance_clock(2400).as_slice(),
+            [
+                GameEvent::TimedOut { player_id: 2 },
+                GameEvent::PlayerRemoved { player_id: 2 },
+                GameEvent::TurnChanged { player_id: 3 },
+            ]
+        ));
+        assert!(game.get_phase() == GamePhase::MoveTiles);
+        assert!(game.shift_tiles(3, SideIndex::new(Side::Top, 1)).is_ok());
+    }
+
+    #[test]
+    fn random_shift_timeout() {
+        let time_control = TimeControl::new(Some(100), None, TimeoutPenalty::RandomShift);
+        let mut game = timed_game(2, time_control);
+        assert!(matches!(
+            game.advance_clock(100).as_slice(),
+            [
+                GameEvent::TimedOut { player_id: 0 },
+                GameEvent::TilesShifted { .. },
+                GameEvent::TurnChanged { player_id: 1 },
+            ]
+        ));
+
+        // The shift only depends on when the time ran out.
+        let mut ticked = timed_game(2, time_control);
+        for now in (0..=150).step_by(10) {
+            ticked.advance_clock(now);
+        }
+        game.advance_clock(150);
+        assert_eq!(format_game(&ticked), format_game(&game));
+
+        let mut untimed = new_game().unwrap();
+        assert!(untimed.advance_clock(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn validate_untrusted_games() {
+        let game = new_game().unwrap();
+        assert_eq!(game.validate(), Ok(()));
+
+        let json = serde_json::to_value(&game).unwrap();
+        let validate = |change: &dyn Fn(&mut serde_json::Value)| {
+            let mut json = json.clone();
+            change(&mut json);
+            serde_json::from_value::<Game>(json).unwrap().validate()
+        };
+
+        assert_eq!(
+            validate(&|json| json["board"]["width"] = 9.into()),
+            Err(InvalidGameError::BoardError(InvalidBoardError::TileCount))
+        );
+        assert_eq!(
+            validate(&|json| json["board"]["height"] = 1.into()),
+            Err(InvalidGameError::BoardError(InvalidBoardError::InvalidSize))
+        );
+        assert_eq!(
+            validate(&|json| json["board"]["tiles"][1]["id"] = 0.into()),
+            Err(InvalidGameError::BoardError(InvalidBoardError::TileIds))
+        );
+        assert_eq!(
+            validate(&|json| json["board"]["free_tile"]["side_with_index"] =
+                serde_json::json!({ "side": "Top", "index": 2 })),
+            Err(InvalidGameError::BoardError(
+                InvalidBoardError::InvalidSideIndex(SideIndex::new(Side::Top, 2))
+            ))
+        );
+        assert_eq!(
+            validate(&|json| json["players"]["players"]["1"]["position"]["x"] = 7.into()),
+            Err(InvalidGameError::PlayersError(
+                InvalidPlayersError::OffBoard(1)
+            ))
+        );
+        assert_eq!(
+            validate(&|json| json["players"]["players"]["2"]["id"] = 3.into()),
+            Err(InvalidGameError::PlayersError(
+                InvalidPlayersError::MismatchedId(2)
+            ))
+        );
+        assert_eq!(
+            validate(&|json| json["players"]["player_turn"] = 4.into()),
+            Err(InvalidGameError::PlayersError(
+                InvalidPlayersError::UnknownTurn(4)
+            ))
+        );
+        assert_eq!(
+            validate(&|json| json["players"]["players"]["0"]["to_collect"][0] = 99.into()),
+            Err(InvalidGameError::PlayersError(
+                InvalidPlayersError::UnknownItem(0)
+            ))
+        );
+        assert_eq!(
+            validate(&|json| json["winner"] = 4.into()),
+            Err(InvalidGameError::UnknownWinner(4))
+        );
+        assert_eq!(
+            validate(&|json| json["players"]["players"]["0"]["team"] = 0.into()),
+            Err(InvalidGameError::MixedTeams)
+        );
+        let err = validate(&|json| json["players"]["players"]["1"]["remaining_time"] = 5.into());
+        assert_eq!(err, Err(InvalidGameError::RemainingTime(1)));
+        assert_eq!(
+            Failure::set_game(err.unwrap_err()).get_kind(),
+            ErrorKind::InvalidGame {
+                reason: InvalidGameReason::RemainingTime { player_id: 1 }
+            }
+        );
+
+        let timed = timed_game(
+            2,
+            TimeControl::new(Some(100), Some(1000), TimeoutPenalty::SkipMove),
+        );
+        assert_eq!(timed.validate(), Ok(()));
+        let json = serde_json::to_value(&timed).unwrap();
+        let validate = |change: &dyn Fn(&mut serde_json::Value)| {
+            let mut json = json.clone();
+            change(&mut json);
+            serde_json::from_value::<Game>(json).unwrap().validate()
+        };
+        assert_eq!(
+            validate(&|json| json["players"]["players"]["0"]["remaining_time"] = 1001.into()),
+            Err(InvalidGameError::RemainingTime(0))
+        );
+        assert_eq!(
+            validate(&|json| json["players"]["players"]["1"]["remaining_time"] = ().into()),
+            Err(InvalidGameError::RemainingTime(1))
+        );
+        assert_eq!(
+            validate(&|json| json["clock"]["turn_time"] = 101.into()),
+            Err(InvalidGameError::TurnTime)
+        );
+
+        assert!(matches!(
+            parse_game("L1T0L2/I0T31I1/L0T2L3 I1 0:0,0:0,0:: 0 S -"),
+            Err(NotationError::InvalidGame(
+                InvalidGameError::NotEnoughPlayers
+            ))
+        ));
+    }
+
+    #[test]
+    fn torus_topology() {
+        let notation = "I1I1I1/I0I0I0/I0I0I0 L0 0:0,0:0,0::/1:2,2:2,2:: 0 M -";
+        let bounded = parse_game(notation).unwrap();
+        let torus = parse_game(&format!("{} torus", notation)).unwrap();
+        assert_eq!(torus.get_board().get_topology(), Topology::Torus);
+        assert_eq!(format_game(&torus), format!("{} torus", notation));
+
+        let (start, goal) = (Position::new(0, 0), Position::new(2, 0));
+        assert_eq!(bounded.get_board().get_path(start, goal).unwrap().len(), 3);
+        assert_eq!(
+            torus.get_board().get_path(start, goal),
+            Some(vec![start, goal])
+        );
+        assert_eq!(start.left(torus.get_board()), Some(goal));
+        assert_eq!(start.left(bounded.get_board()), None);
+        assert_eq!(start.top(torus.get_board()), Some(Position::new(0, 2)));
+
+        let mut reachable = torus.get_board().get_reachable(Position::new(0, 1));
+        reachable.sort();
+        assert_eq!(reachable, [Position::new(0, 1), Position::new(0, 2)]);
+
+        let settings = GameStartSettings::new([0, 1].into(), 7, 6).with_topology(Topology::Torus);
+        let mut game = Game::new(settings).unwrap();
+        assert_eq!(game.get_board().get_topology(), Topology::Torus);
+        assert!(game.shift_tiles(0, SideIndex::new(Side::Right, 1)).is_ok());
+        let mut board = game.get_board().clone();
+        let changes = board.shift_tiles(SideIndex::new(Side::Top, 1)).unwrap();
+        assert_eq!(changes[&Position::new(1, 6)], Position::new(1, 0));
+        assert_eq!(changes[&Position::new(1, 2)], Position::new(1, 3));
+    }
+
+    #[test]
+    fn new_tile_variants() {
+        let tile = |variant, rotation| Tile::new(0, variant, rotation, None).get_connection();
+        assert_eq!(tile(TileVariant::DeadEnd, Rotation::Ninety), [Side::Right]);
+        assert_eq!(
+            tile(TileVariant::DeadEnd, Rotation::TwoSeventy),
+            [Side::Left]
+        );
+        assert_eq!(tile(TileVariant::Cross, Rotation::Ninety), Side::ALL);
+        assert_eq!(tile(TileVariant::Bridge, Rotation::OneEighty), Side::ALL);
+
+        // Dead ends lead into the middle tile from all four sides.
+        let notation = "L1D2L2/D1B0D3/L0D0L3 I0 0:1,0:1,0::/1:0,1:0,1:: 0 M -";
+        let bridge = parse_game(notation).unwrap();
+        let cross = parse_game(&notation.replace("B0", "X0")).unwrap();
+        assert_eq!(format_game(&bridge), notation);
+
+        let reachable = |game: &Game, x, y| game.get_board().get_reachable(Position::new(x, y));
+        let column = [(1, 0), (1, 1), (1, 2)].map(|(x, y)| Position::new(x, y));
+        let row = [(0, 1), (1, 1), (2, 1)].map(|(x, y)| Position::new(x, y));
+        assert_eq!(reachable(&bridge, 1, 0), column);
+        assert_eq!(reachable(&bridge, 0, 1), row);
+        assert_eq!(reachable(&bridge, 1, 1).len(), 5);
+        assert_eq!(reachable(&cross, 1, 0).len(), 5);
+        assert_eq!(
+            bridge.get_board().get_path(column[0], column[2]),
+            Some(column.to_vec())
+        );
+        assert_eq!(bridge.get_board().get_path(column[0], row[0]), None);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mix = TileMix::new(0, 0, 0).with_bridge(1);
+        let board = Board::with_tile_mix(7, 7, mix, &mut rng).unwrap();
+        let bridges = (0..7)
+            .flat_map(|y| (0..7).map(move |x| Position::new(x, y)))
+            .filter(|&position| board[position].get_variant() == TileVariant::Bridge)
+            .count();
+        assert_eq!(bridges, 7 * 7 - 4 * 4);
+        assert_eq!(
+            board.get_free_tile().tile().get_variant(),
+            TileVariant::Bridge
+        );
+
+        assert!(matches!(
+            Board::with_tile_mix(7, 7, TileMix::new(0, 0, 0), &mut rng),
+            Err(NewBoardError::InvalidTileMix)
+        ));
+        let settings =
+            GameStartSettings::new([0, 1].into(), 7, 6).with_tile_mix(TileMix::new(0, 0, 0));
+        let err = Failure::start_game(Game::new(settings).err().unwrap());
+        assert_eq!(err.get_kind(), ErrorKind::InvalidTileMix);
+    }
+
+    #[test]
+    fn custom_assortment() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let positions = |board: &Board| {
+            let (width, height) = (board.get_width(), board.get_height());
+            (0..height).flat_map(move |y| (0..width).map(move |x| Position::new(x, y)))
+        };
+        let count = |board: &Board, variant| {
+            positions(board)
+                .map(|position| board[position])
+                .chain([*board.get_free_tile().tile()])
+                .filter(|tile| tile.get_variant() == variant)
+                .count()
+        };
+
+        // A 7x7 board has 34 movable tiles, the free tile included.
+        let mix = TileMix::new(10, 0, 3)
+            .with_cross(21)
+            .with_exact_counts(true);
+        let board = Board::with_tile_mix(7, 7, mix, &mut rng).unwrap();
+        assert_eq!(count(&board, TileVariant::Cross), 21);
+        assert_eq!(count(&board, TileVariant::IShape), 3);
+        assert_eq!(count(&board, TileVariant::LShape), 10 + 4);
+        assert!(matches!(
+            Board::with_tile_mix(7, 7, mix.with_cross(20), &mut rng),
+            Err(NewBoardError::WrongTileCount { expected: 34 })
+        ));
+
+        let items = |board: &Board| {
+            positions(board)
+                .filter_map(|position| board[position].get_item())
+                .chain(board.get_free_tile().tile().get_item())
+                .count()
+        };
+        let assortment = |item_count| {
+            Board::with_assortment(7, 7, TileMix::default(), item_count, &mut rng.clone())
+        };
+        for (item_count, expected) in [
+            (ItemCount::Classic, 24),
+            (ItemCount::Exact { count: 1 }, 1),
+            (ItemCount::Exact { count: 40 }, 40),
+            (ItemCount::Exact { count: 46 }, 46),
+            (ItemCount::Density { percent: 50 }, 23),
+        ] {
+            let board = assortment(item_count).unwrap();
+            assert_eq!(board.get_number_of_items(), expected);
+            assert_eq!(items(&board), expected);
+            assert!(board.validate().is_ok());
+        }
+        assert!(matches!(
+            assortment(ItemCount::Exact { count: 0 }),
+            Err(NewBoardError::NoItems)
+        ));
+        assert!(matches!(
+            assortment(ItemCount::Density { percent: 101 }),
+            Err(NewBoardError::TooManyItems { max: 46 })
+        ));
+
+        // Custom item counts give even the smallest board something to find.
+        let settings = GameStartSettings::new([0, 1].into(), 3, 2)
+            .with_item_count(ItemCount::Exact { count: 4 })
+            .with_seed(1);
+        let game = Game::new(settings.clone()).unwrap();
+        assert_eq!(game.get_board().get_number_of_items(), 4);
+        assert!(game.validate().is_ok());
+        let notation = format_game(&game);
+        assert!(notation.ends_with(" items4"));
+        let parsed = parse_game(&notation).unwrap();
+        assert_eq!(parsed.get_board().get_number_of_items(), 4);
+        assert_eq!(format_game(&parsed), notation);
+        let too_many = settings
+            .clone()
+            .with_item_count(ItemCount::Exact { count: 7 });
+        let err = Failure::start_game(Game::new(too_many).err().unwrap());
+        assert_eq!(err.get_kind(), ErrorKind::TooManyItems { max: 6 });
+        let none = settings.with_item_count(ItemCount::Exact { count: 0 });
+        let err = Failure::start_game(Game::new(none).err().unwrap());
+        assert_eq!(err.get_kind(), ErrorKind::NoItems);
+    }
+
+    #[test]
+    fn turn_ownership() {
+        let mut game = new_game().unwrap();
+        let side_index = SideIndex::new(Side::Top, 1);
+        assert_eq!(game.current_player_id(), 0);
+
+        let err = game.shift_tiles(1, side_index).unwrap_err();
+        assert!(matches!(err, GameError::NotYourTurn));
+        assert_eq!(
+            Failure::shift_tiles(err, 1, side_index).get_kind(),
+            ErrorKind::NotYourTurn { player_id: 1 }
+        );
+        assert!(game.shift_tiles(0, side_index).is_ok());
+
+        let position = game.get_players().get(1).unwrap().get_position();
+        let err = game.move_player(1, position).err().unwrap();
+        assert!(matches!(err, GameError::NotYourTurn));
+        assert_eq!(
+            Failure::move_player(err, 1, position).get_kind(),
+            ErrorKind::NotYourTurn { player_id: 1 }
+        );
+        assert!(matches!(
+            game.move_player(5, position).err().unwrap(),
+            GameError::NotYourTurn
+        ));
+
+        assert!(game.move_player(0, Position::new(0, 0)).is_ok());
+        assert_eq!(game.current_player_id(), 1);
+    }
+
     #[test]
     fn game_actions() {
         let mut game = new_game().unwrap();
-        assert!(game.shift_tiles(SideIndex::new(Side::Top, 1)).is_ok());
+        assert!(game.shift_tiles(0, SideIndex::new(Side::Top, 1)).is_ok());
         assert!(game.remove_player(0).is_ok());
         assert!(game.move_player(1, Position::new(6, 0)).is_ok());
     }
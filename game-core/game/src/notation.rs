@@ -0,0 +1,575 @@
+//! A compact, human-readable notation for games and turns, meant to be pasted
+//! into bug reports and chats.
+//!
+//! A game is written as six space separated fields:
+//!
+//! ```text
+//! <board> <free tile> <players> <turn> <phase> <winner>
+//! ```
+//!
+//! - The board lists its rows from top to bottom, separated by `/`. All rows
+//!   have the same number of tiles, but need not be as many as rows. Each tile
+//!   is its variant (`L`, `T`, `I`, `X` for a cross, `D` for a dead end or `B`
+//!   for a bridge), its rotation as a number of quarter turns (`0` to `3`) and
+//!   optionally the number of its item, e.g. `T2` or `L112` (an `L` rotated by
+//!   90° holding item 12).
+//! - The free tile is written like a board tile, followed by `@` and the side
+//!   index it was pushed out at (e.g. `@B3` for the bottom of column 3), if any.
+//! - The players are separated by `/`, each as
+//!   `<id>:<x>,<y>:<start x>,<start y>:<collected>:<to collect>`, where both
+//!   item lists are separated by `.` and the next item to collect is last.
+//!   Players who have taken turns are followed by `:<turns>`, and players in
+//!   a team by `:<turns>:<team>`.
+//! - The turn is the id of the current player.
+//! - The phase is `S` if the tiles have to be shifted, `M` if the player has
+//!   to move.
+//! - The winner is its id, or `-` if there is none.
+//!
+//! Games on a torus have a further field `torus`, and games in which shifts
+//! may not push players off the board have a field `block` after it. Then
+//! follows the [`WinCondition`]: `center` to reach the center, `collect<n>`
+//! to collect `n` items or `rounds<n>` to collect the most items in `n`
+//! rounds. Boards with a custom [`ItemCount`] end with `items<n>` for their
+//! `n` items. Games played to the end have a field `end`. All of them are
+//! left out for the usual rules.
+//!
+//! Last come the standings, if they are not just the winner and their team
+//! placed first: `placed` followed by the finished players separated by `/`,
+//! each as `<id>:<placement>:<turns>:<collected>`, followed by `:<team>` for
+//! players in a team.
+//!
+//! [`ItemCount`]: crate::board::ItemCount
+//!
+//! A turn is written as its rotation, side index and destination, e.g.
+//! `R90 T3 (4,2)`.
+//!
+//! Formatting a parsed notation gives back the same text. Tile ids are not
+//! part of the notation and are assigned in reading order when parsing.
+//! Games that fail [`Game::validate`] are rejected. The clocks of timed games
+//! are not part of the notation.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Write},
+};
+
+use crate::{
+    board::{Board, Topology},
+    game::{Game, GamePhase, InvalidGameError, PushOffRule, Standing, Turn, WinCondition},
+    player::{Player, PlayerId, Players, Position},
+    tile::{FreeTile, Item, Rotation, Side, SideIndex, Tile, TileVariant},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum NotationError {
+    MissingField(&'static str),
+    TrailingInput(String),
+    InvalidTile(String),
+    InvalidBoardShape,
+    InvalidSideIndex(String),
+    InvalidPlayer(String),
+    InvalidPosition(String),
+    InvalidRotation(String),
+    InvalidNumber(String),
+    InvalidPhase(String),
+    InvalidStanding(String),
+    UnknownPlayer(PlayerId),
+    DuplicatePlayer(PlayerId),
+    InvalidGame(InvalidGameError),
+}
+
+pub fn format_game(game: &Game) -> String {
+    let mut out = String::new();
+    let board = game.get_board();
+    for y in 0..board.get_height() {
+        if y > 0 {
+            out.push('/');
+        }
+        for x in 0..board.get_width() {
+            write_tile(&mut out, &board[Position::new(x, y)]);
+        }
+    }
+
+    out.push(' ');
+    let free_tile = board.get_free_tile();
+    write_tile(&mut out, free_tile.tile());
+    if let Some(side_index) = free_tile.get_side_index() {
+        let _ = write!(out, "@{}", format_side_index(side_index));
+    }
+
+    out.push(' ');
+    let players: Vec<_> = game.get_players().iter().map(format_player).collect();
+    out.push_str(&players.join("/"));
+
+    let _ = write!(
+        out,
+        " {} {} ",
+        game.current_player_id(),
+        match game.get_phase() {
+            GamePhase::MoveTiles => 'S',
+            GamePhase::MovePlayer => 'M',
+        }
+    );
+    match game.get_winner() {
+        Some(winner) => {
+            let _ = write!(out, "{}", winner);
+        }
+        None => out.push('-'),
+    }
+
+    if board.get_topology() == Topology::Torus {
+        out.push_str(" torus");
+    }
+    if game.get_push_off_rule() == PushOffRule::Block {
+        out.push_str(" block");
+    }
+    match game.get_win_condition() {
+        WinCondition::ReturnToStart => {}
+        WinCondition::ReachCenter => out.push_str(" center"),
+        WinCondition::CollectItems { count } => {
+            let _ = write!(out, " collect{}", count);
+        }
+        WinCondition::MostItems { rounds } => {
+            let _ = write!(out, " rounds{}", rounds);
+        }
+    }
+    if let Some(number_of_items) = board.get_custom_number_of_items() {
+        let _ = write!(out, " items{}", number_of_items);
+    }
+    if game.plays_to_end() {
+        out.push_str(" end");
+    }
+    if !game.has_implied_standings() {
+        let standings: Vec<_> = game.get_standings().iter().map(format_standing).collect();
+        let _ = write!(out, " placed{}", standings.join("/"));
+    }
+
+    out
+}
+
+pub fn parse_game(notation: &str) -> Result<Game, NotationError> {
+    let mut fields = notation.split_whitespace();
+    let mut next_field = |name| fields.next().ok_or(NotationError::MissingField(name));
+
+    let rows = next_field("board")?
+        .split('/')
+        .map(parse_tiles)
+        .collect::<Result<Vec<_>, _>>()?;
+    let (width, height) = (rows[0].len(), rows.len());
+    if width == 0 || rows.iter().any(|row| row.len() != width) {
+        return Err(NotationError::InvalidBoardShape);
+    }
+    let tiles = rows
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(id, (variant, rotation, item))| Tile::new(id, variant, rotation, item))
+        .collect();
+
+    let free_tile = next_field("free tile")?;
+    let (tile, side_index) = match free_tile.split_once('@') {
+        Some((tile, side_index)) => (tile, Some(parse_side_index(side_index)?)),
+        None => (free_tile, None),
+    };
+    let (variant, rotation, item) = match parse_tiles(tile)?.as_slice() {
+        [tile] => *tile,
+        _ => return Err(NotationError::InvalidTile(tile.into())),
+    };
+    let mut free_tile = FreeTile::new(Tile::new(width * height, variant, rotation, item));
+    if let Some(side_index) = side_index {
+        free_tile.set_side_index(side_index);
+    }
+
+    let mut players = BTreeMap::new();
+    for player in next_field("players")?.split('/') {
+        let player = parse_player(player)?;
+        let id = player.get_id();
+        if players.insert(id, player).is_some() {
+            return Err(NotationError::DuplicatePlayer(id));
+        }
+    }
+
+    let turn = parse_number(next_field("turn")?)?;
+    if !players.contains_key(&turn) {
+        return Err(NotationError::UnknownPlayer(turn));
+    }
+
+    let phase = match next_field("phase")? {
+        "S" => GamePhase::MoveTiles,
+        "M" => GamePhase::MovePlayer,
+        phase => return Err(NotationError::InvalidPhase(phase.into())),
+    };
+
+    let winner = match next_field("winner")? {
+        "-" => None,
+        winner => Some(parse_number(winner)?),
+    };
+
+    let mut rest = fields.next();
+    let topology = match rest {
+        Some("torus") => {
+            rest = fields.next();
+            Topology::Torus
+        }
+        _ => Topology::Bounded,
+    };
+    let push_off = match rest {
+        Some("block") => {
+            rest = fields.next();
+            PushOffRule::Block
+        }
+        _ => PushOffRule::Wrap,
+    };
+    let win_condition = match rest.map(parse_win_condition).transpose()?.flatten() {
+        Some(win_condition) => {
+            rest = fields.next();
+            win_condition
+        }
+        None => WinCondition::ReturnToStart,
+    };
+    let number_of_items = match rest.and_then(|field| field.strip_prefix("items")) {
+        Some(number_of_items) => {
+            rest = fields.next();
+            Some(parse_number(number_of_items)?)
+        }
+        None => None,
+    };
+    let play_to_end = rest == Some("end");
+    if play_to_end {
+        rest = fields.next();
+    }
+    let standings = match rest.and_then(|field| field.strip_prefix("placed")) {
+        Some(standings) => {
+            rest = fields.next();
+            Some(
+                standings
+                    .split('/')
+                    .filter(|standing| !standing.is_empty())
+                    .map(parse_standing)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        }
+        None => None,
+    };
+
+    if let Some(rest) = rest {
+        return Err(NotationError::TrailingInput(rest.into()));
+    }
+
+    let board = Board::from_parts(tiles, width, height, free_tile, number_of_items);
+    for player in players.values() {
+        for position in [player.get_position(), player.get_start_position()] {
+            if board.get_tile(position).is_none() {
+                return Err(NotationError::InvalidPosition(format_position(position)));
+            }
+        }
+    }
+
+    let game = Game::from_parts(
+        board.with_topology(topology),
+        Players::from_parts(players, turn),
+        phase,
+        winner,
+        push_off,
+    )
+    .with_win_condition(win_condition)
+    .with_play_to_end(play_to_end);
+    let game = match standings {
+        Some(standings) => game.with_standings(standings),
+        None => game,
+    };
+    game.validate().map_err(NotationError::InvalidGame)?;
+    Ok(game)
+}
+
+pub fn format_turn(turn: &Turn) -> String {
+    let destination = turn.get_destination();
+    format!(
+        "R{} {} ({},{})",
+        match turn.get_rotation() {
+            Rotation::Zero => 0,
+            Rotation::Ninety => 90,
+            Rotation::OneEighty => 180,
+            Rotation::TwoSeventy => 270,
+        },
+        format_side_index(turn.get_side_index()),
+        destination.get_x(),
+        destination.get_y()
+    )
+}
+
+pub fn parse_turn(notation: &str) -> Result<Turn, NotationError> {
+    let mut fields = notation.split_whitespace();
+    let mut next_field = |name| fields.next().ok_or(NotationError::MissingField(name));
+
+    let rotation = next_field("rotation")?;
+    let rotation = match rotation {
+        "R0" => Rotation::Zero,
+        "R90" => Rotation::Ninety,
+        "R180" => Rotation::OneEighty,
+        "R270" => Rotation::TwoSeventy,
+        _ => return Err(NotationError::InvalidRotation(rotation.into())),
+    };
+
+    let side_index = parse_side_index(next_field("side index")?)?;
+
+    let destination = next_field("destination")?;
+    let destination = destination
+        .strip_prefix('(')
+        .and_then(|d| d.strip_suffix(')'))
+        .ok_or_else(|| NotationError::InvalidPosition(destination.into()))
+        .and_then(parse_position)?;
+
+    if let Some(rest) = fields.next() {
+        return Err(NotationError::TrailingInput(rest.into()));
+    }
+
+    Ok(Turn::new(rotation, side_index, destination))
+}
+
+fn write_tile(out: &mut String, tile: &Tile) {
+    out.push(match tile.get_variant() {
+        TileVariant::LShape => 'L',
+        TileVariant::TShape => 'T',
+        TileVariant::IShape => 'I',
+        TileVariant::Cross => 'X',
+        TileVariant::DeadEnd => 'D',
+        TileVariant::Bridge => 'B',
+    });
+    out.push(match tile.get_rotation() {
+        Rotation::Zero => '0',
+        Rotation::Ninety => '1',
+        Rotation::OneEighty => '2',
+        Rotation::TwoSeventy => '3',
+    });
+    if let Some(item) = tile.get_item() {
+        let _ = write!(out, "{}", item.get_id());
+    }
+}
+
+/// Parses a sequence of tiles, each a variant letter, a rotation digit and
+/// optionally the digits of its item.
+fn parse_tiles(tiles: &str) -> Result<Vec<(TileVariant, Rotation, Option<Item>)>, NotationError> {
+    let invalid = || NotationError::InvalidTile(tiles.into());
+    let mut parsed = Vec::new();
+    let mut chars = tiles.chars().peekable();
+
+    while let Some(variant) = chars.next() {
+        let variant = match variant {
+            'L' => TileVariant::LShape,
+            'T' => TileVariant::TShape,
+            'I' => TileVariant::IShape,
+            'X' => TileVariant::Cross,
+            'D' => TileVariant::DeadEnd,
+            'B' => TileVariant::Bridge,
+            _ => return Err(invalid()),
+        };
+        let rotation = match chars.next() {
+            Some('0') => Rotation::Zero,
+            Some('1') => Rotation::Ninety,
+            Some('2') => Rotation::OneEighty,
+            Some('3') => Rotation::TwoSeventy,
+            _ => return Err(invalid()),
+        };
+
+        let mut item = String::new();
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            item.push(digit);
+        }
+        let item = match item.as_str() {
+            "" => None,
+            item => match parse_number(item)? {
+                0 => return Err(invalid()),
+                id => Some(Item::new(id)),
+            },
+        };
+
+        parsed.push((variant, rotation, item));
+    }
+
+    if parsed.is_empty() {
+        return Err(invalid());
+    }
+    Ok(parsed)
+}
+
+fn format_side_index(side_index: SideIndex) -> String {
+    let side = match side_index.get_side() {
+        Side::Top => 'T',
+        Side::Right => 'R',
+        Side::Bottom => 'B',
+        Side::Left => 'L',
+    };
+    format!("{}{}", side, side_index.get_index())
+}
+
+fn parse_side_index(side_index: &str) -> Result<SideIndex, NotationError> {
+    let invalid = || NotationError::InvalidSideIndex(side_index.into());
+    let mut chars = side_index.chars();
+    let side = match chars.next() {
+        Some('T') => Side::Top,
+        Some('R') => Side::Right,
+        Some('B') => Side::Bottom,
+        Some('L') => Side::Left,
+        _ => return Err(invalid()),
+    };
+    let index = chars.as_str().parse().map_err(|_| invalid())?;
+    Ok(SideIndex::new(side, index))
+}
+
+/// Parses a win condition, or returns `None` if the field is none.
+fn parse_win_condition(field: &str) -> Result<Option<WinCondition>, NotationError> {
+    if field == "center" {
+        return Ok(Some(WinCondition::ReachCenter));
+    }
+    if let Some(count) = field.strip_prefix("collect") {
+        let count = parse_number(count)?;
+        return Ok(Some(WinCondition::CollectItems { count }));
+    }
+    if let Some(rounds) = field.strip_prefix("rounds") {
+        let rounds = parse_number(rounds)?;
+        return Ok(Some(WinCondition::MostItems { rounds }));
+    }
+    Ok(None)
+}
+
+fn format_standing(standing: &Standing) -> String {
+    let mut out = format!(
+        "{}:{}:{}:{}",
+        standing.get_player_id(),
+        standing.get_placement(),
+        standing.get_turns(),
+        standing.get_collected()
+    );
+    if let Some(team) = standing.get_team() {
+        let _ = write!(out, ":{}", team);
+    }
+    out
+}
+
+fn parse_standing(standing: &str) -> Result<Standing, NotationError> {
+    let invalid = || NotationError::InvalidStanding(standing.into());
+    let numbers = standing
+        .split(':')
+        .map(|number| parse_number(number).map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    match numbers[..] {
+        [id, placement, turns, collected] => {
+            Ok(Standing::new(id, placement, turns, collected, None))
+        }
+        [id, placement, turns, collected, team] => {
+            Ok(Standing::new(id, placement, turns, collected, Some(team)))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn format_player(player: &Player) -> String {
+    let items = |items: &[Item]| {
+        items
+            .iter()
+            .map(|item| item.get_id().to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+
+    let mut out = format!(
+        "{}:{}:{}:{}:{}",
+        player.get_id(),
+        format_position(player.get_position()),
+        format_position(player.get_start_position()),
+        items(player.get_collected()),
+        items(player.get_to_collect())
+    );
+    if player.get_turns() > 0 || player.get_team().is_some() {
+        let _ = write!(out, ":{}", player.get_turns());
+    }
+    if let Some(team) = player.get_team() {
+        let _ = write!(out, ":{}", team);
+    }
+    out
+}
+
+fn parse_player(player: &str) -> Result<Player, NotationError> {
+    let fields: Vec<_> = player.split(':').collect();
+    let [id, position, start_position, collected, to_collect, optional @ ..] = fields.as_slice()
+    else {
+        return Err(NotationError::InvalidPlayer(player.into()));
+    };
+    let (turns, team) = match optional {
+        [] => (0, None),
+        [turns] => (parse_number(turns)?, None),
+        [turns, team] => (parse_number(turns)?, Some(parse_number(team)?)),
+        _ => return Err(NotationError::InvalidPlayer(player.into())),
+    };
+
+    let items = |items: &str| -> Result<Vec<Item>, NotationError> {
+        items
+            .split('.')
+            .filter(|item| !item.is_empty())
+            .map(|item| match parse_number(item)? {
+                0 => Err(NotationError::InvalidPlayer(player.into())),
+                id => Ok(Item::new(id)),
+            })
+            .collect()
+    };
+
+    let mut player = Player::from_parts(
+        parse_number(id)?,
+        parse_position(position)?,
+        parse_position(start_position)?,
+        items(collected)?,
+        items(to_collect)?,
+        turns,
+    );
+    player.set_team(team);
+    Ok(player)
+}
+
+fn format_position(position: Position) -> String {
+    format!("{},{}", position.get_x(), position.get_y())
+}
+
+fn parse_position(position: &str) -> Result<Position, NotationError> {
+    let (x, y) = position
+        .split_once(',')
+        .ok_or_else(|| NotationError::InvalidPosition(position.into()))?;
+    Ok(Position::new(parse_number(x)?, parse_number(y)?))
+}
+
+fn parse_number(number: &str) -> Result<usize, NotationError> {
+    number
+        .parse()
+        .map_err(|_| NotationError::InvalidNumber(number.into()))
+}
+
+impl Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::MissingField(field) => write!(f, "Missing {}", field),
+            NotationError::TrailingInput(rest) => write!(f, "Unexpected input: {}", rest),
+            NotationError::InvalidTile(tile) => write!(f, "Invalid tile: {}", tile),
+            NotationError::InvalidBoardShape => f.write_str("Board is not rectangular"),
+            NotationError::InvalidSideIndex(side_index) => {
+                write!(f, "Invalid side index: {}", side_index)
+            }
+            NotationError::InvalidPlayer(player) => write!(f, "Invalid player: {}", player),
+            NotationError::InvalidPosition(position) => {
+                write!(f, "Invalid position: {}", position)
+            }
+            NotationError::InvalidRotation(rotation) => {
+                write!(f, "Invalid rotation: {}", rotation)
+            }
+            NotationError::InvalidNumber(number) => write!(f, "Invalid number: {}", number),
+            NotationError::InvalidPhase(phase) => write!(f, "Invalid phase: {}", phase),
+            NotationError::InvalidStanding(standing) => {
+                write!(f, "Invalid standing: {}", standing)
+            }
+            NotationError::UnknownPlayer(id) => write!(f, "No such player: {}", id),
+            NotationError::DuplicatePlayer(id) => write!(f, "Player {} appears twice", id),
+            NotationError::InvalidGame(err) => write!(f, "Invalid game: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
@@ -1,14 +1,24 @@
-use crate::{board::Board, tile::Item};
+use crate::{
+    board::Board,
+    game::WinCondition,
+    tile::{Item, Side},
+};
 use rand::{
     seq::{IteratorRandom, SliceRandom},
     Rng,
 };
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+};
 use ts_interop::ts_interop;
 
 #[cfg_attr(feature = "wasm", tsify::declare)]
 pub type PlayerId = usize;
 
+#[cfg_attr(feature = "wasm", tsify::declare)]
+pub type TeamId = usize;
+
 #[ts_interop]
 #[derive(Clone)]
 pub struct Players {
@@ -25,10 +35,23 @@ pub struct Player {
     start_position: Position,
     collected: Vec<Item>,
     to_collect: Vec<Item>,
+    /// The time left on the player's clock in a timed game, in milliseconds.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    remaining_time: Option<u64>,
+    /// The team of the player in team mode. Teammates share their item cards
+    /// and win together.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    team: Option<TeamId>,
+    /// The number of turns the player has taken.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    turns: usize,
 }
 
 #[ts_interop]
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct Position {
     x: usize,
     y: usize,
@@ -37,28 +60,62 @@ pub struct Position {
 pub struct PlayerMove {
     pub winner: Option<PlayerId>,
     pub path: Vec<Position>,
+    pub collected: Option<Item>,
 }
 
+#[derive(Debug)]
 pub enum MoveError {
     InvalidPosition,
     InvalidPlayer,
     UnreachablePosition,
 }
 
+#[derive(Debug)]
+pub enum RemovePlayerError {
+    InvalidPlayer,
+    /// The last player is the winner and stays in the game.
+    LastPlayer,
+}
+
+/// Why players that were not created by [`Players::new`], e.g. deserialized
+/// ones, are inconsistent with themselves or the board.
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvalidPlayersError {
+    NoPlayers,
+    /// The player is stored under a different id than its own.
+    MismatchedId(PlayerId),
+    /// It is the turn of a player that does not exist.
+    UnknownTurn(PlayerId),
+    /// The position or start position of the player is not on the board.
+    OffBoard(PlayerId),
+    /// An item card of the player is not on any tile.
+    UnknownItem(PlayerId),
+}
+
 impl Players {
     pub fn new(ids: BTreeSet<PlayerId>, items_per_player: usize, board: &Board) -> Option<Self> {
+        Self::with_rng(ids, items_per_player, board, &mut rand::thread_rng())
+    }
+
+    /// Deals the item cards using the given random number generator, so that
+    /// the same generator state always produces the same deal.
+    pub fn with_rng(
+        ids: BTreeSet<PlayerId>,
+        items_per_player: usize,
+        board: &Board,
+        rng: &mut impl Rng,
+    ) -> Option<Self> {
         if ids.len() < 2 {
             return None;
         }
 
         let player_turn = *ids.first().unwrap();
-        let mut rng = rand::thread_rng();
         let mut items = get_items_to_collect(
             board.get_number_of_items(),
             ids.len() * items_per_player,
-            &mut rng,
+            rng,
         );
-        items.shuffle(&mut rng);
+        items.shuffle(rng);
 
         let players = ids
             .into_iter()
@@ -67,7 +124,7 @@ impl Players {
                     id,
                     Player::new(
                         id,
-                        get_start_position(id, board.get_side_length()),
+                        get_start_position(id, board.get_width(), board.get_height()),
                         items.drain(0..items_per_player).collect(),
                     ),
                 )
@@ -82,62 +139,204 @@ impl Players {
         })
     }
 
+    pub(crate) fn from_parts(players: BTreeMap<PlayerId, Player>, player_turn: PlayerId) -> Self {
+        Self {
+            players,
+            player_turn,
+        }
+    }
+
+    /// Checks that the players are consistent with themselves and the board,
+    /// so that no action on them can panic.
+    pub fn validate(&self, board: &Board) -> Result<(), InvalidPlayersError> {
+        if self.players.is_empty() {
+            return Err(InvalidPlayersError::NoPlayers);
+        }
+
+        if !self.players.contains_key(&self.player_turn) {
+            return Err(InvalidPlayersError::UnknownTurn(self.player_turn));
+        }
+
+        let free_item = board.get_free_tile().tile().get_item();
+        for (&id, player) in &self.players {
+            if id != player.id {
+                return Err(InvalidPlayersError::MismatchedId(id));
+            }
+
+            if board.get_tile(player.position).is_none()
+                || board.get_tile(player.start_position).is_none()
+            {
+                return Err(InvalidPlayersError::OffBoard(id));
+            }
+
+            let on_board =
+                |&item: &Item| Some(item) == free_item || board.find_item(item).is_some();
+            if !player
+                .collected
+                .iter()
+                .chain(&player.to_collect)
+                .all(on_board)
+            {
+                return Err(InvalidPlayersError::UnknownItem(id));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn current_player(&self) -> &Player {
         &self.players[&self.player_turn]
     }
 
+    pub(crate) fn current_player_mut(&mut self) -> &mut Player {
+        self.players.get_mut(&self.player_turn).unwrap()
+    }
+
+    pub fn get(&self, player_id: PlayerId) -> Option<&Player> {
+        self.players.get(&player_id)
+    }
+
+    /// Iterates over the players in the order of their turn, starting with
+    /// the lowest id.
+    pub fn iter(&self) -> impl Iterator<Item = &Player> {
+        self.players.values()
+    }
+
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Player> {
         self.players.values_mut()
     }
 
-    pub fn remove_player(&mut self, player_id: PlayerId) -> Result<Option<PlayerId>, ()> {
-        if self.players.remove(&player_id).is_none() {
-            return Err(());
+    /// Iterates over the player and its teammates, in the order of their turn.
+    pub fn teammates(&self, player_id: PlayerId) -> impl Iterator<Item = &Player> {
+        let player = self.players.get(&player_id);
+        self.players
+            .values()
+            .filter(move |other| player.is_some_and(|player| player.is_teammate(other)))
+    }
+
+    /// The number of sides still competing, which are the teams in team mode
+    /// and the single players otherwise.
+    pub fn count_sides(&self) -> usize {
+        let teams: BTreeSet<_> = self.players.values().filter_map(Player::get_team).collect();
+        let solo = self.players.values().filter(|p| p.team.is_none()).count();
+        teams.len() + solo
+    }
+
+    pub fn remove_player(
+        &mut self,
+        player_id: PlayerId,
+    ) -> Result<Option<PlayerId>, RemovePlayerError> {
+        if !self.players.contains_key(&player_id) {
+            return Err(RemovePlayerError::InvalidPlayer);
         }
 
         if self.players.len() == 1 {
-            return Ok(self.players.first_key_value().map(|(id, _)| *id));
+            return Err(RemovePlayerError::LastPlayer);
         }
 
+        self.players.remove(&player_id);
+
         if player_id == self.player_turn {
             self.next_player_turn();
         }
 
+        if self.count_sides() == 1 {
+            return Ok(self.players.first_key_value().map(|(id, _)| *id));
+        }
+
         Ok(None)
     }
 
+    /// Moves the player and collects the item it lands on. The move wins if
+    /// it meets the win condition, which ends the turn of the player unless
+    /// it did.
     pub fn move_player(
         &mut self,
         player_id: PlayerId,
         position: Position,
         board: &Board,
+        win_condition: WinCondition,
     ) -> Result<PlayerMove, MoveError> {
         if board.get_tile(position).is_none() {
             return Err(MoveError::InvalidPosition);
         }
 
         if let Some(player) = self.players.get_mut(&player_id) {
-            return match board.get_path(player.get_position(), position) {
-                Some(path) => {
-                    player.set_position(position);
-                    let mut result = PlayerMove { winner: None, path };
-
-                    if player.get_next_to_collect().is_none() && player.is_at_start() {
-                        result.winner = Some(player_id);
-                    } else {
-                        player.try_collect_item(board);
-                        self.next_player_turn();
-                    }
-
-                    Ok(result)
-                }
-                None => Err(MoveError::UnreachablePosition),
+            debug_assert_eq!(player_id, self.player_turn);
+
+            let Some(path) = board.get_path(player.get_position(), position) else {
+                return Err(MoveError::UnreachablePosition);
             };
+            player.set_position(position);
+            player.count_turn();
+            let mut result = PlayerMove {
+                winner: None,
+                path,
+                collected: None,
+            };
+
+            // Only collecting enough items wins right away, the other
+            // conditions have to be met at the start of the move.
+            let mut finished = self.has_finished(player_id, board, win_condition);
+            if !finished {
+                result.collected = self.try_collect_team_item(player_id, board);
+                finished = matches!(win_condition, WinCondition::CollectItems { .. })
+                    && self.has_finished(player_id, board, win_condition);
+            }
+
+            if finished {
+                result.winner = Some(player_id);
+            } else {
+                self.next_player_turn();
+            }
+
+            return Ok(result);
         }
         Err(MoveError::InvalidPlayer)
     }
 
-    fn next_player_turn(&mut self) {
+    /// Whether the player, together with their team, meets the win condition
+    /// where they stand.
+    fn has_finished(
+        &self,
+        player_id: PlayerId,
+        board: &Board,
+        win_condition: WinCondition,
+    ) -> bool {
+        let player = &self.players[&player_id];
+        let team_done = || {
+            self.teammates(player_id)
+                .all(|p| p.get_next_to_collect().is_none())
+        };
+        match win_condition {
+            WinCondition::ReturnToStart => team_done() && player.is_at_start(),
+            WinCondition::ReachCenter => team_done() && player.position == board.get_center(),
+            WinCondition::CollectItems { count } => self.collected_by_team(player_id) >= count,
+            WinCondition::MostItems { .. } => false,
+        }
+    }
+
+    /// The number of items the player and their teammates have collected.
+    pub fn collected_by_team(&self, player_id: PlayerId) -> usize {
+        self.teammates(player_id).map(|p| p.collected.len()).sum()
+    }
+
+    /// Collects the item the player stands on if it is the next item of the
+    /// player or, in team mode, of a teammate. The player's own card is
+    /// preferred, the card is taken off the stack of its owner.
+    fn try_collect_team_item(&mut self, player_id: PlayerId, board: &Board) -> Option<Item> {
+        let item = board[self.players[&player_id].position].get_item()?;
+        let owner = self
+            .teammates(player_id)
+            .filter(|p| p.get_next_to_collect() == Some(item))
+            .min_by_key(|p| p.id != player_id)?
+            .id;
+        let owner = self.players.get_mut(&owner).unwrap();
+        owner.collected.push(owner.to_collect.pop().unwrap());
+        Some(item)
+    }
+
+    pub(crate) fn next_player_turn(&mut self) {
         self.player_turn = self
             .players
             .range(self.player_turn + 1..)
@@ -156,17 +355,86 @@ impl Player {
             start_position,
             collected: Vec::new(),
             to_collect,
+            remaining_time: None,
+            team: None,
+            turns: 0,
         }
     }
 
+    pub(crate) fn from_parts(
+        id: PlayerId,
+        position: Position,
+        start_position: Position,
+        collected: Vec<Item>,
+        to_collect: Vec<Item>,
+        turns: usize,
+    ) -> Self {
+        Self {
+            id,
+            position,
+            start_position,
+            collected,
+            to_collect,
+            remaining_time: None,
+            team: None,
+            turns,
+        }
+    }
+
+    pub fn get_id(&self) -> PlayerId {
+        self.id
+    }
+
     pub fn get_position(&self) -> Position {
         self.position
     }
 
+    pub fn get_start_position(&self) -> Position {
+        self.start_position
+    }
+
+    pub fn get_collected(&self) -> &[Item] {
+        &self.collected
+    }
+
+    /// The items still to collect, the next one being last.
+    pub fn get_to_collect(&self) -> &[Item] {
+        &self.to_collect
+    }
+
     pub fn get_next_to_collect(&self) -> Option<Item> {
         self.to_collect.last().copied()
     }
 
+    pub fn get_remaining_time(&self) -> Option<u64> {
+        self.remaining_time
+    }
+
+    pub(crate) fn set_remaining_time(&mut self, remaining_time: Option<u64>) {
+        self.remaining_time = remaining_time;
+    }
+
+    pub fn get_team(&self) -> Option<TeamId> {
+        self.team
+    }
+
+    pub(crate) fn set_team(&mut self, team: Option<TeamId>) {
+        self.team = team;
+    }
+
+    pub fn get_turns(&self) -> usize {
+        self.turns
+    }
+
+    pub(crate) fn count_turn(&mut self) {
+        self.turns += 1;
+    }
+
+    /// Whether the other player is this player or on the same team.
+    pub fn is_teammate(&self, other: &Player) -> bool {
+        self.id == other.id || (self.team.is_some() && self.team == other.team)
+    }
+
     pub fn is_at_start(&self) -> bool {
         self.position == self.start_position
     }
@@ -174,13 +442,6 @@ impl Player {
     pub fn set_position(&mut self, position: Position) {
         self.position = position;
     }
-
-    pub fn try_collect_item(&mut self, board: &Board) {
-        let next = self.get_next_to_collect();
-        if next.is_some() && next == board[self.position].get_item() {
-            self.collected.push(self.to_collect.pop().unwrap());
-        }
-    }
 }
 
 impl Position {
@@ -196,53 +457,76 @@ impl Position {
         self.y
     }
 
-    pub fn top(&self, _: &Board) -> Option<Self> {
-        if self.y == 0 {
-            return None;
-        }
-        Some(Self {
-            y: self.y - 1,
-            ..*self
-        })
+    pub fn top(&self, board: &Board) -> Option<Self> {
+        board.get_neighbour(*self, Side::Top)
     }
 
     pub fn bottom(&self, board: &Board) -> Option<Self> {
-        if self.y > board.get_side_length() - 2 {
-            return None;
-        }
-        Some(Self {
-            y: self.y + 1,
-            ..*self
+        board.get_neighbour(*self, Side::Bottom)
+    }
+
+    pub fn left(&self, board: &Board) -> Option<Self> {
+        board.get_neighbour(*self, Side::Left)
+    }
+
+    pub fn right(&self, board: &Board) -> Option<Self> {
+        board.get_neighbour(*self, Side::Right)
+    }
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MoveError::InvalidPosition => "Position is not on the board",
+            MoveError::InvalidPlayer => "No such player exists",
+            MoveError::UnreachablePosition => "Position is not reachable by player",
         })
     }
+}
 
-    pub fn left(&self, _: &Board) -> Option<Self> {
-        if self.x == 0 {
-            return None;
-        }
-        Some(Self {
-            x: self.x - 1,
-            ..*self
+impl std::error::Error for MoveError {}
+
+impl Display for RemovePlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RemovePlayerError::InvalidPlayer => "No such player exists",
+            RemovePlayerError::LastPlayer => "The last player cannot be removed",
         })
     }
+}
 
-    pub fn right(&self, board: &Board) -> Option<Self> {
-        if self.x > board.get_side_length() - 2 {
-            return None;
+impl std::error::Error for RemovePlayerError {}
+
+impl Display for InvalidPlayersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidPlayersError::NoPlayers => f.write_str("There are no players"),
+            InvalidPlayersError::MismatchedId(id) => {
+                write!(f, "Player {} is stored under a different id", id)
+            }
+            InvalidPlayersError::UnknownTurn(id) => {
+                write!(f, "It is the turn of player {}, who does not exist", id)
+            }
+            InvalidPlayersError::OffBoard(id) => write!(f, "Player {} is not on the board", id),
+            InvalidPlayersError::UnknownItem(id) => {
+                write!(
+                    f,
+                    "Player {} has an item card for an item not on the board",
+                    id
+                )
+            }
         }
-        Some(Self {
-            x: self.x + 1,
-            ..*self
-        })
     }
 }
 
-fn get_start_position(id: usize, side_length: usize) -> Position {
+impl std::error::Error for InvalidPlayersError {}
+
+fn get_start_position(id: usize, width: usize, height: usize) -> Position {
     match id % 4 {
         0 => Position::new(0, 0),
-        1 => Position::new(side_length - 1, 0),
-        2 => Position::new(side_length - 1, side_length - 1),
-        _ => Position::new(0, side_length - 1),
+        1 => Position::new(width - 1, 0),
+        2 => Position::new(width - 1, height - 1),
+        _ => Position::new(0, height - 1),
     }
 }
 
@@ -253,9 +537,7 @@ fn get_items_to_collect(
 ) -> Vec<Item> {
     let mut items: Vec<_> = (1..=num_board_items).map(Item::new).collect();
 
-    let extra = num_item_cards
-        .checked_sub(num_board_items)
-        .unwrap_or_default();
+    let extra = num_item_cards.saturating_sub(num_board_items);
 
     items.extend(
         (0..extra)
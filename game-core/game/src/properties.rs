@@ -0,0 +1,356 @@
+//! Property tests: random sequences of legal and illegal actions are played
+//! on random games, and the invariants of the rules are checked after every
+//! single action.
+
+use std::collections::BTreeSet;
+
+use proptest::{prelude::*, sample::Index};
+
+use crate::{
+    board::{Board, ItemCount, TileMix, Topology},
+    event::GameEvent,
+    game::{Game, GameStartSettings, PushOffRule, WinCondition},
+    player::{PlayerId, Position},
+    replay::Action,
+    tile::{Rotation, Side, SideIndex},
+};
+
+/// An action to try, which may or may not be legal in the game it is played
+/// on.
+#[derive(Clone, Debug)]
+enum Attempt {
+    Rotate(Rotation),
+    /// Shifts as the current player, so that it mostly succeeds.
+    Shift(Side, usize),
+    /// Shifts as any player, at any index.
+    ShiftAny(PlayerId, Side, usize),
+    /// Moves the current player to one of the reachable positions.
+    MoveReachable(Index),
+    /// Moves any player to any position, possibly off the board.
+    MoveAny(PlayerId, usize, usize),
+    Remove(PlayerId),
+}
+
+#[derive(Debug)]
+struct Setup {
+    players: usize,
+    width: usize,
+    height: usize,
+    topology: Topology,
+    tile_mix: TileMix,
+    item_count: ItemCount,
+    push_off: PushOffRule,
+    /// Plays in two teams of the even and the odd player ids.
+    teams: bool,
+    play_to_end: bool,
+    win_condition: WinCondition,
+    seed: u64,
+}
+
+fn rotation() -> impl Strategy<Value = Rotation> {
+    prop::sample::select(Rotation::ALL.to_vec())
+}
+
+fn side() -> impl Strategy<Value = Side> {
+    prop::sample::select(Side::ALL.to_vec())
+}
+
+fn setup() -> impl Strategy<Value = Setup> {
+    let odd = || (1..=5usize).prop_map(|n| 2 * n + 1);
+    let push_off = prop::sample::select(vec![PushOffRule::Wrap, PushOffRule::Block]);
+    let win_condition = prop::sample::select(vec![
+        WinCondition::ReturnToStart,
+        WinCondition::ReachCenter,
+        WinCondition::CollectItems { count: 1 },
+        WinCondition::CollectItems { count: 3 },
+        WinCondition::MostItems { rounds: 1 },
+        WinCondition::MostItems { rounds: 4 },
+    ]);
+    let tile_mix = prop::sample::select(vec![
+        TileMix::default(),
+        TileMix::default()
+            .with_cross(4)
+            .with_dead_end(4)
+            .with_bridge(4),
+        TileMix::new(0, 0, 1).with_bridge(1),
+    ]);
+    let item_count = prop::sample::select(vec![
+        ItemCount::Classic,
+        ItemCount::Exact { count: 1 },
+        ItemCount::Density { percent: 100 },
+    ]);
+    (
+        2..=4usize,
+        odd(),
+        odd(),
+        prop::bool::ANY,
+        tile_mix,
+        item_count,
+        push_off,
+        prop::bool::ANY,
+        prop::bool::ANY,
+        win_condition,
+        any::<u64>(),
+    )
+        .prop_filter("3x3 boards have no items", |&(_, width, height, ..)| {
+            width * height > 9
+        })
+        .prop_map(
+            |(
+                players,
+                width,
+                height,
+                torus,
+                tile_mix,
+                item_count,
+                push_off,
+                teams,
+                play_to_end,
+                win_condition,
+                seed,
+            )| {
+                Setup {
+                    players,
+                    width,
+                    height,
+                    topology: if torus {
+                        Topology::Torus
+                    } else {
+                        Topology::Bounded
+                    },
+                    tile_mix,
+                    item_count,
+                    push_off,
+                    teams,
+                    play_to_end,
+                    win_condition,
+                    seed,
+                }
+            },
+        )
+}
+
+fn attempt() -> impl Strategy<Value = Attempt> {
+    prop_oneof![
+        2 => rotation().prop_map(Attempt::Rotate),
+        4 => (side(), (0..6usize).prop_map(|i| 2 * i + 1))
+            .prop_map(|(side, index)| Attempt::Shift(side, index)),
+        1 => (0..5usize, side(), 0..14usize)
+            .prop_map(|(player_id, side, index)| Attempt::ShiftAny(player_id, side, index)),
+        4 => any::<Index>().prop_map(Attempt::MoveReachable),
+        1 => (0..5usize, 0..14usize, 0..14usize)
+            .prop_map(|(player_id, x, y)| Attempt::MoveAny(player_id, x, y)),
+        1 => (0..5usize).prop_map(Attempt::Remove),
+    ]
+}
+
+impl Attempt {
+    fn to_action(&self, game: &Game) -> Action {
+        let player_id = game.current_player_id();
+        match *self {
+            Attempt::Rotate(rotation) => Action::RotateFreeTile { rotation },
+            Attempt::Shift(side, index) => Action::ShiftTiles {
+                player_id,
+                side_index: SideIndex::new(side, index),
+            },
+            Attempt::ShiftAny(player_id, side, index) => Action::ShiftTiles {
+                player_id,
+                side_index: SideIndex::new(side, index),
+            },
+            Attempt::MoveReachable(index) => {
+                let mut reachable = game.currently_reachable().unwrap_or_default();
+                reachable.sort();
+                let position = match reachable.as_slice() {
+                    [] => Position::new(0, 0),
+                    positions => *index.get(positions),
+                };
+                Action::MovePlayer {
+                    player_id,
+                    position,
+                }
+            }
+            Attempt::MoveAny(player_id, x, y) => Action::MovePlayer {
+                player_id,
+                position: Position::new(x, y),
+            },
+            Attempt::Remove(player_id) => Action::RemovePlayer { player_id },
+        }
+    }
+}
+
+fn positions(board: &Board) -> impl Iterator<Item = Position> {
+    let width = board.get_width();
+    (0..width * board.get_height()).map(move |i| Position::new(i % width, i / width))
+}
+
+fn tile_ids(board: &Board) -> Vec<usize> {
+    positions(board)
+        .map(|position| board[position].get_id())
+        .collect()
+}
+
+fn check_tiles(board: &Board) -> Result<(), TestCaseError> {
+    let size = board.get_width() * board.get_height();
+    let mut ids: Vec<_> = tile_ids(board);
+    prop_assert_eq!(ids.len(), size);
+    ids.push(board.get_free_tile().tile().get_id());
+    ids.sort();
+    prop_assert_eq!(ids, (0..=size).collect::<Vec<_>>());
+    Ok(())
+}
+
+fn check_items(board: &Board) -> Result<(), TestCaseError> {
+    let mut items: Vec<_> = positions(board)
+        .filter_map(|position| board[position].get_item())
+        .chain(board.get_free_tile().tile().get_item())
+        .map(|item| item.get_id())
+        .collect();
+    items.sort();
+    prop_assert_eq!(items, (1..=board.get_number_of_items()).collect::<Vec<_>>());
+    Ok(())
+}
+
+fn check_fixed_tiles(board: &Board, initial: &Board) -> Result<(), TestCaseError> {
+    for position in positions(board).filter(|p| p.get_x() % 2 == 0 && p.get_y() % 2 == 0) {
+        prop_assert_eq!(board[position].get_id(), initial[position].get_id());
+        prop_assert_eq!(
+            board[position].get_rotation(),
+            initial[position].get_rotation()
+        );
+    }
+    Ok(())
+}
+
+fn check_players(game: &Game) -> Result<(), TestCaseError> {
+    let board = game.get_board();
+    for player in game.get_players().iter() {
+        prop_assert!(board.get_tile(player.get_position()).is_some());
+        prop_assert!(board.get_tile(player.get_start_position()).is_some());
+    }
+    prop_assert!(game.get_players().get(game.current_player_id()).is_some());
+    if let Some(winner) = game.get_winner() {
+        prop_assert!(game.has_won(winner));
+    }
+    let placements: Vec<_> = game
+        .get_standings()
+        .iter()
+        .map(|s| s.get_placement())
+        .collect();
+    prop_assert!(placements.windows(2).all(|w| w[0] <= w[1]));
+    // Games played to the end are only over once everyone is placed.
+    let everyone_placed = game
+        .get_players()
+        .iter()
+        .all(|p| game.get_standing(p.get_id()).is_some());
+    prop_assert_eq!(
+        game.is_over(),
+        game.get_winner().is_some() && (!game.plays_to_end() || everyone_placed)
+    );
+    Ok(())
+}
+
+/// Every reachable position has a path, and every path steps between
+/// connected neighbours only.
+fn check_paths(board: &Board, start: Position) -> Result<(), TestCaseError> {
+    let reachable = board.get_reachable(start);
+    prop_assert!(reachable.contains(&start));
+    prop_assert_eq!(
+        reachable.iter().collect::<BTreeSet<_>>().len(),
+        reachable.len()
+    );
+
+    for &goal in &reachable {
+        let path = board.get_path(start, goal);
+        prop_assert!(path.is_some());
+        let path = path.unwrap();
+        prop_assert_eq!(path.first(), Some(&start));
+        prop_assert_eq!(path.last(), Some(&goal));
+
+        // Bridges may only be left opposite the side they were entered at.
+        let mut entered_at = None;
+        for step in path.windows(2) {
+            let side = Side::ALL.into_iter().find(|side| {
+                board.get_neighbour(step[0], *side) == Some(step[1])
+                    && board[step[0]].get_exit_mask(entered_at) & side.mask() != 0
+                    && board[step[1]].get_connection_mask() & side.opposite().mask() != 0
+            });
+            prop_assert!(
+                side.is_some(),
+                "{:?} -> {:?} is not connected",
+                step[0],
+                step[1]
+            );
+            entered_at = side.map(|side| side.opposite());
+        }
+    }
+
+    for position in positions(board).filter(|p| !reachable.contains(p)) {
+        prop_assert!(board.get_path(start, position).is_none());
+    }
+    Ok(())
+}
+
+/// Players only end up on the other side of the board if they may be pushed
+/// off.
+fn check_push_off(game: &Game, events: &[GameEvent]) -> Result<(), TestCaseError> {
+    if game.get_push_off_rule() != PushOffRule::Block {
+        return Ok(());
+    }
+    for event in events {
+        if let GameEvent::TilesShifted { moved_players, .. } = event {
+            for moved in moved_players {
+                let (from, to) = (moved.get_from(), moved.get_to());
+                prop_assert_eq!(
+                    from.get_x().abs_diff(to.get_x()) + from.get_y().abs_diff(to.get_y()),
+                    1
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn actions_keep_invariants(
+        setup in setup(),
+        attempts in prop::collection::vec(attempt(), 0..60),
+    ) {
+        let settings = GameStartSettings::new((0..setup.players).collect(), setup.width, 3)
+            .with_size(setup.width, setup.height)
+            .with_topology(setup.topology)
+            .with_tile_mix(setup.tile_mix)
+            .with_item_count(setup.item_count)
+            .with_push_off_rule(setup.push_off)
+            .with_play_to_end(setup.play_to_end)
+            .with_win_condition(setup.win_condition)
+            .with_seed(setup.seed);
+        let settings = if setup.teams {
+            let (even, odd) = (0..setup.players).partition(|id| id % 2 == 0);
+            settings.with_teams(vec![even, odd])
+        } else {
+            settings
+        };
+        let mut game = Game::new(settings).unwrap();
+        let initial = game.get_board().clone();
+
+        for attempt in attempts {
+            let before = serde_json::to_string(&game).unwrap();
+            let action = attempt.to_action(&game);
+
+            match action.apply(&mut game) {
+                Ok(events) => check_push_off(&game, &events)?,
+                // Failed actions must not change anything.
+                Err(_) => prop_assert_eq!(serde_json::to_string(&game).unwrap(), before),
+            }
+
+            prop_assert_eq!(game.validate(), Ok(()));
+            let board = game.get_board();
+            check_tiles(board)?;
+            check_items(board)?;
+            check_fixed_tiles(board, &initial)?;
+            check_players(&game)?;
+            check_paths(board, game.get_players().current_player().get_position())?;
+        }
+    }
+}
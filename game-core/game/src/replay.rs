@@ -0,0 +1,151 @@
+use ts_interop::ts_interop;
+
+use crate::{
+    error::{ActionKind, ErrorKind, Failure},
+    event::GameEvent,
+    game::{Game, GameStartSettings},
+    player::{PlayerId, Position},
+    tile::{Rotation, SideIndex},
+};
+
+/// An action that changes the state of a [`Game`].
+#[ts_interop]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(tag = "type")]
+pub enum Action {
+    RotateFreeTile {
+        rotation: Rotation,
+    },
+    ShiftTiles {
+        player_id: PlayerId,
+        side_index: SideIndex,
+    },
+    MovePlayer {
+        player_id: PlayerId,
+        position: Position,
+    },
+    RemovePlayer {
+        player_id: PlayerId,
+    },
+    /// Advances the clock of a timed game, see [`Game::advance_clock`].
+    AdvanceClock {
+        now: u64,
+    },
+}
+
+/// A whole match, stored as the settings it was started with and every action
+/// taken since. Any intermediate state can be rebuilt by replaying the actions.
+#[ts_interop]
+#[derive(Clone)]
+pub struct Replay {
+    settings: GameStartSettings,
+    actions: Vec<Action>,
+}
+
+impl Action {
+    pub fn kind(&self) -> ActionKind {
+        match self {
+            Action::RotateFreeTile { .. } => ActionKind::RotateFreeTile,
+            Action::ShiftTiles { .. } => ActionKind::ShiftTiles,
+            Action::MovePlayer { .. } => ActionKind::MovePlayer,
+            Action::RemovePlayer { .. } => ActionKind::RemovePlayer,
+            Action::AdvanceClock { .. } => ActionKind::AdvanceClock,
+        }
+    }
+
+    /// Applies the action, returning the events it caused.
+    pub fn apply(&self, game: &mut Game) -> Result<Vec<GameEvent>, Failure> {
+        match *self {
+            Action::RotateFreeTile { rotation } => {
+                if game.rotate_free_tile(rotation) {
+                    Ok(Vec::new())
+                } else {
+                    Err(Failure::new(self.kind(), ErrorKind::GameOver))
+                }
+            }
+            Action::ShiftTiles {
+                player_id,
+                side_index,
+            } => game
+                .shift_tiles(player_id, side_index)
+                .map_err(|err| Failure::shift_tiles(err, player_id, side_index)),
+            Action::MovePlayer {
+                player_id,
+                position,
+            } => game
+                .move_player(player_id, position)
+                .map_err(|err| Failure::move_player(err, player_id, position)),
+            Action::RemovePlayer { player_id } => game
+                .remove_player(player_id)
+                .map_err(|err| Failure::remove_player(err, player_id)),
+            Action::AdvanceClock { now } => Ok(game.advance_clock(now)),
+        }
+    }
+}
+
+impl Replay {
+    /// Starts recording a new match. If the settings have no seed, a random
+    /// one is chosen and kept in the replay.
+    pub fn new(settings: GameStartSettings) -> Self {
+        Self {
+            settings: settings.seeded(),
+            actions: Vec::new(),
+        }
+    }
+
+    pub fn get_settings(&self) -> &GameStartSettings {
+        &self.settings
+    }
+
+    pub fn get_actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    pub fn initial_game(&self) -> Result<Game, Failure> {
+        Game::new(self.settings.clone()).map_err(Failure::start_game)
+    }
+
+    /// Applies the action to the game and records it if it succeeded.
+    pub fn apply(&mut self, game: &mut Game, action: Action) -> Result<(), Failure> {
+        action.apply(game)?;
+        self.record(action);
+        Ok(())
+    }
+
+    /// Records an action that has already been applied to the game.
+    pub fn record(&mut self, action: Action) {
+        self.actions.push(action);
+    }
+
+    /// Forgets every action after the first `action_count` ones.
+    pub fn truncate(&mut self, action_count: usize) {
+        self.actions.truncate(action_count);
+    }
+
+    /// Rebuilds the game as it was after the first `action_count` actions.
+    pub fn game_at(&self, action_count: usize) -> Result<Game, Failure> {
+        let actions = self
+            .actions
+            .get(..action_count)
+            .ok_or_else(|| Failure::new(ActionKind::LoadReplay, ErrorKind::ReplayTooShort))?;
+
+        let mut game = self.initial_game()?;
+        for action in actions {
+            action.apply(&mut game)?;
+        }
+        Ok(game)
+    }
+
+    /// Rebuilds the game as it is after every recorded action.
+    pub fn final_game(&self) -> Result<Game, Failure> {
+        self.game_at(self.actions.len())
+    }
+}
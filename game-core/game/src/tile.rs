@@ -3,7 +3,7 @@ use std::num::NonZeroUsize;
 use ts_interop::ts_interop;
 
 #[ts_interop]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Tile {
     id: usize,
     variant: TileVariant,
@@ -12,7 +12,7 @@ pub struct Tile {
 }
 
 #[ts_interop]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum TileVariant {
     /// 0°   is └
     /// 90°  is ┌
@@ -29,10 +29,21 @@ pub enum TileVariant {
     /// 180° is │
     /// 270° is ─
     IShape,
+    /// ┼ in every rotation.
+    Cross,
+    /// 0°   is ╵
+    /// 90°  is ╶
+    /// 180° is ╷
+    /// 270° is ╴
+    DeadEnd,
+    /// ╋ in every rotation, but the horizontal passage crosses the vertical
+    /// one without connecting to it: whoever enters the tile can only leave
+    /// it straight ahead or the way they came.
+    Bridge,
 }
 
 #[ts_interop]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Rotation {
     Zero,
     Ninety,
@@ -42,7 +53,7 @@ pub enum Rotation {
 
 #[ts_interop]
 #[repr(transparent)]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Item(#[cfg_attr(feature = "wasm", tsify(type = "number"))] NonZeroUsize);
 
 #[ts_interop]
@@ -54,7 +65,7 @@ pub struct FreeTile {
 
 /// The index always goes from left to right or from top to bottom.
 #[ts_interop]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct SideIndex {
     side: Side,
     index: usize,
@@ -62,7 +73,7 @@ pub struct SideIndex {
 
 /// The side of the board where the free tile is located.
 #[ts_interop]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Side {
     Top,
     Right,
@@ -81,35 +92,76 @@ impl Tile {
     }
 
     pub fn get_connection(&self) -> Vec<Side> {
-        use Rotation as R;
+        let mask = self.get_connection_mask();
+        Side::ALL
+            .into_iter()
+            .filter(|side| mask & side.mask() != 0)
+            .collect()
+    }
+
+    /// The connected sides as a bit mask of [`Side::mask`]s.
+    pub fn get_connection_mask(&self) -> u8 {
         use Side::*;
-        use TileVariant as V;
-
-        match (self.variant, self.rotation) {
-            (V::LShape, R::Zero) => vec![Top, Right],
-            (V::LShape, R::Ninety) => vec![Bottom, Right],
-            (V::LShape, R::OneEighty) => vec![Bottom, Left],
-            (V::LShape, R::TwoSeventy) => vec![Top, Left],
-            (V::TShape, R::Zero) => vec![Bottom, Left, Right],
-            (V::TShape, R::Ninety) => vec![Top, Bottom, Left],
-            (V::TShape, R::OneEighty) => vec![Top, Left, Right],
-            (V::TShape, R::TwoSeventy) => vec![Top, Bottom, Right],
-            (V::IShape, R::Zero | R::OneEighty) => vec![Top, Bottom],
-            (V::IShape, R::Ninety | R::TwoSeventy) => vec![Left, Right],
+
+        let unrotated = match self.variant {
+            TileVariant::LShape => Top.mask() | Right.mask(),
+            TileVariant::TShape => Right.mask() | Bottom.mask() | Left.mask(),
+            TileVariant::IShape => Top.mask() | Bottom.mask(),
+            TileVariant::Cross | TileVariant::Bridge => 0b1111,
+            TileVariant::DeadEnd => Top.mask(),
+        };
+
+        // Each quarter turn moves every connection one side clockwise.
+        let turns = self.rotation as u32;
+        ((unrotated << turns) | (unrotated >> (4 - turns))) & 0b1111
+    }
+
+    /// The sides the tile can be left at after entering it at the given side,
+    /// or at any side if it was not entered, e.g. by starting on it. Only
+    /// bridges differ from [`Tile::get_connection_mask`].
+    pub fn get_exit_mask(&self, entered_at: Option<Side>) -> u8 {
+        match (self.variant, entered_at) {
+            (TileVariant::Bridge, Some(side)) => side.mask() | side.opposite().mask(),
+            _ => self.get_connection_mask(),
         }
     }
 
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+
+    pub fn get_variant(&self) -> TileVariant {
+        self.variant
+    }
+
+    pub fn get_rotation(&self) -> Rotation {
+        self.rotation
+    }
+
     pub fn get_item(&self) -> Option<Item> {
         self.item
     }
 }
 
+impl Rotation {
+    pub const ALL: [Rotation; 4] = [
+        Rotation::Zero,
+        Rotation::Ninety,
+        Rotation::OneEighty,
+        Rotation::TwoSeventy,
+    ];
+}
+
 impl Item {
     pub fn new(id: usize) -> Self {
         assert!(id != 0);
         // Safety: id != 0
         Self(unsafe { NonZeroUsize::new_unchecked(id) })
     }
+
+    pub fn get_id(&self) -> usize {
+        self.0.get()
+    }
 }
 
 impl FreeTile {
@@ -120,6 +172,10 @@ impl FreeTile {
         }
     }
 
+    pub fn tile(&self) -> &Tile {
+        &self.tile
+    }
+
     pub fn get_side_index(&self) -> Option<SideIndex> {
         self.side_with_index
     }
@@ -137,6 +193,24 @@ impl FreeTile {
     }
 }
 
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Top, Side::Right, Side::Bottom, Side::Left];
+
+    /// A single bit, in clockwise order starting with [`Side::Top`].
+    pub fn mask(&self) -> u8 {
+        1 << *self as u8
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Side::Top => Side::Bottom,
+            Side::Right => Side::Left,
+            Side::Bottom => Side::Top,
+            Side::Left => Side::Right,
+        }
+    }
+}
+
 impl SideIndex {
     pub fn new(side: Side, index: usize) -> Self {
         Self { side, index }
@@ -151,15 +225,8 @@ impl SideIndex {
     }
 
     pub fn shift(&self) -> Self {
-        let side = match self.side {
-            Side::Top => Side::Bottom,
-            Side::Right => Side::Left,
-            Side::Bottom => Side::Top,
-            Side::Left => Side::Right,
-        };
-
         Self {
-            side,
+            side: self.side.opposite(),
             index: self.index,
         }
     }
@@ -0,0 +1,188 @@
+//! Redacted views of a [`Game`] for its participants. A player sees their own
+//! item cards and those of their teammates, but of the other players only how
+//! many items they have collected and how many they have left. Spectators see
+//! no cards at all.
+//!
+//! Servers and hosts send views instead of the game itself, so that nobody
+//! can learn what the others are looking for by inspecting the traffic.
+
+use std::collections::BTreeMap;
+
+use ts_interop::ts_interop;
+
+use crate::{
+    board::Board,
+    clock::Clock,
+    game::{Game, GamePhase, PushOffRule, Standing, WinCondition},
+    player::{Player, PlayerId, Position, TeamId},
+    tile::Item,
+};
+
+/// Everything a participant of a game is allowed to see.
+#[ts_interop]
+#[derive(Clone)]
+pub struct GameView {
+    /// The player the view is for, `None` for spectators.
+    viewer: Option<PlayerId>,
+    board: Board,
+    /// The players in the order of their turn.
+    players: BTreeMap<PlayerId, PlayerView>,
+    player_turn: PlayerId,
+    phase: GamePhase,
+    winner: Option<PlayerId>,
+    standings: Vec<Standing>,
+    push_off: PushOffRule,
+    win_condition: WinCondition,
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    clock: Option<Clock>,
+}
+
+/// A player as seen by a participant of the game.
+#[ts_interop]
+#[derive(Clone)]
+pub struct PlayerView {
+    id: PlayerId,
+    position: Position,
+    start_position: Position,
+    collected_count: usize,
+    to_collect_count: usize,
+    /// The collected items, only present in the view of the player and
+    /// their teammates.
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    collected: Option<Vec<Item>>,
+    /// The items still to collect, the next one being last. Only present in
+    /// the view of the player and their teammates.
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    to_collect: Option<Vec<Item>>,
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    remaining_time: Option<u64>,
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    team: Option<TeamId>,
+}
+
+impl GameView {
+    pub(crate) fn new(game: &Game, viewer: Option<PlayerId>) -> Self {
+        let players = game.get_players();
+        let viewer_player = viewer.and_then(|viewer| players.get(viewer));
+        Self {
+            viewer,
+            board: game.get_board().clone(),
+            players: players
+                .iter()
+                .map(|player| (player.get_id(), PlayerView::new(player, viewer_player)))
+                .collect(),
+            player_turn: players.current_player().get_id(),
+            phase: game.get_phase(),
+            winner: game.get_winner(),
+            standings: game.get_standings().to_vec(),
+            push_off: game.get_push_off_rule(),
+            win_condition: game.get_win_condition(),
+            clock: game.get_clock().copied(),
+        }
+    }
+
+    pub fn get_viewer(&self) -> Option<PlayerId> {
+        self.viewer
+    }
+
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn get_player(&self, player_id: PlayerId) -> Option<&PlayerView> {
+        self.players.get(&player_id)
+    }
+
+    /// The players in the order of their turn.
+    pub fn iter_players(&self) -> impl Iterator<Item = &PlayerView> {
+        self.players.values()
+    }
+
+    pub fn get_player_turn(&self) -> PlayerId {
+        self.player_turn
+    }
+
+    pub fn get_phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    pub fn get_winner(&self) -> Option<PlayerId> {
+        self.winner
+    }
+
+    pub fn get_standings(&self) -> &[Standing] {
+        &self.standings
+    }
+
+    pub fn get_push_off_rule(&self) -> PushOffRule {
+        self.push_off
+    }
+
+    pub fn get_win_condition(&self) -> WinCondition {
+        self.win_condition
+    }
+
+    pub fn get_clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+}
+
+impl PlayerView {
+    fn new(player: &Player, viewer: Option<&Player>) -> Self {
+        let is_visible = viewer.is_some_and(|viewer| viewer.is_teammate(player));
+        Self {
+            id: player.get_id(),
+            position: player.get_position(),
+            start_position: player.get_start_position(),
+            collected_count: player.get_collected().len(),
+            to_collect_count: player.get_to_collect().len(),
+            collected: is_visible.then(|| player.get_collected().to_vec()),
+            to_collect: is_visible.then(|| player.get_to_collect().to_vec()),
+            remaining_time: player.get_remaining_time(),
+            team: player.get_team(),
+        }
+    }
+
+    pub fn get_id(&self) -> PlayerId {
+        self.id
+    }
+
+    pub fn get_position(&self) -> Position {
+        self.position
+    }
+
+    pub fn get_start_position(&self) -> Position {
+        self.start_position
+    }
+
+    pub fn get_collected_count(&self) -> usize {
+        self.collected_count
+    }
+
+    pub fn get_to_collect_count(&self) -> usize {
+        self.to_collect_count
+    }
+
+    /// The collected items, if the view is the player's or a teammate's.
+    pub fn get_collected(&self) -> Option<&[Item]> {
+        self.collected.as_deref()
+    }
+
+    /// The items still to collect, the next one being last, if the view is
+    /// the player's or a teammate's.
+    pub fn get_to_collect(&self) -> Option<&[Item]> {
+        self.to_collect.as_deref()
+    }
+
+    pub fn get_next_to_collect(&self) -> Option<Item> {
+        self.to_collect.as_ref()?.last().copied()
+    }
+
+    pub fn get_remaining_time(&self) -> Option<u64> {
+        self.remaining_time
+    }
+
+    pub fn get_team(&self) -> Option<TeamId> {
+        self.team
+    }
+}
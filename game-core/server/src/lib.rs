@@ -0,0 +1,295 @@
+pub mod protocol;
+pub mod room;
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, Mutex},
+    time,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    protocol::{ClientMessage, ServerMessage},
+    room::{ClientId, Outgoing, Rooms},
+};
+
+/// How often the clocks of timed games are advanced between messages.
+const TICK: Duration = Duration::from_millis(100);
+
+/// The state shared by every connection.
+#[derive(Default)]
+struct Shared {
+    rooms: Rooms,
+    senders: HashMap<ClientId, mpsc::UnboundedSender<ServerMessage>>,
+}
+
+/// Accepts WebSocket connections on the listener until it fails.
+pub async fn serve(listener: TcpListener) -> std::io::Result<()> {
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    let next_client_id = AtomicU64::new(0);
+    tokio::spawn(tick(shared.clone()));
+
+    loop {
+        let (stream, address) = listener.accept().await?;
+        let client_id = next_client_id.fetch_add(1, Ordering::Relaxed);
+        log::info!("Client {} connected from {}", client_id, address);
+        tokio::spawn(handle_connection(stream, client_id, shared.clone()));
+    }
+}
+
+/// Advances the clocks regularly, so that players run out of time even when
+/// nobody sends anything.
+async fn tick(shared: Arc<Mutex<Shared>>) {
+    let mut interval = time::interval(TICK);
+    loop {
+        interval.tick().await;
+        let mut shared = shared.lock().await;
+        let outgoing = shared.rooms.advance_clocks(now());
+        shared.send(outgoing);
+    }
+}
+
+/// Milliseconds since the Unix epoch, the time of the game clocks.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+async fn handle_connection(stream: TcpStream, client_id: ClientId, shared: Arc<Mutex<Shared>>) {
+    let socket = match tokio_tungstenite::accept_async(stream).await {
+        Ok(socket) => socket,
+        Err(err) => {
+            log::warn!("Handshake with client {} failed: {}", client_id, err);
+            return;
+        }
+    };
+    let (mut write, mut read) = socket.split();
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    shared.lock().await.senders.insert(client_id, sender);
+
+    let writer = tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            let Ok(text) = serde_json::to_string(&message) else {
+                continue;
+            };
+            if write.send(Message::Text(text)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(Ok(message)) = read.next().await {
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let mut shared = shared.lock().await;
+        // Time has to pass before the action, so that players who ran out of
+        // time cannot act anymore.
+        let mut outgoing = shared.rooms.advance_clocks(now());
+        match serde_json::from_str::<ClientMessage>(&text) {
+            Ok(message) => outgoing.extend(shared.rooms.handle(client_id, message)),
+            Err(err) => outgoing.push((
+                client_id,
+                ServerMessage::Error {
+                    message: format!("Invalid message: {}", err),
+                },
+            )),
+        }
+        shared.send(outgoing);
+    }
+
+    let mut shared = shared.lock().await;
+    shared.rooms.disconnect(client_id);
+    shared.senders.remove(&client_id);
+    writer.abort();
+    log::info!("Client {} disconnected", client_id);
+}
+
+impl Shared {
+    fn send(&self, outgoing: Outgoing) {
+        for (client_id, message) in outgoing {
+            if let Some(sender) = self.senders.get(&client_id) {
+                let _ = sender.send(message);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{SinkExt, StreamExt};
+    use game::{
+        clock::{TimeControl, TimeoutPenalty},
+        event::GameEvent,
+        game::GameStartSettings,
+        tile::{Side, SideIndex},
+    };
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    use crate::{
+        protocol::{ClientMessage, ServerMessage},
+        room::Rooms,
+    };
+
+    fn settings() -> GameStartSettings {
+        GameStartSettings::new([0, 1].into(), 7, 6).with_seed(1)
+    }
+
+    fn shift() -> ClientMessage {
+        ClientMessage::ShiftTiles {
+            side_index: SideIndex::new(Side::Top, 1),
+        }
+    }
+
+    #[test]
+    fn rooms_validate_actions() {
+        let mut rooms = Rooms::default();
+        let created = rooms.handle(
+            1,
+            ClientMessage::CreateRoom {
+                settings: settings(),
+            },
+        );
+        assert!(matches!(
+            created[0],
+            (1, ServerMessage::RoomCreated { room_id: 1 })
+        ));
+
+        let join = |player_id| ClientMessage::JoinRoom {
+            room_id: 1,
+            player_id: Some(player_id),
+        };
+        assert!(matches!(
+            rooms.handle(1, shift())[0].1,
+            ServerMessage::Error { .. }
+        ));
+        assert!(matches!(
+            rooms.handle(1, join(0))[0].1,
+            ServerMessage::Joined { .. }
+        ));
+        assert!(matches!(
+            rooms.handle(2, join(0))[0].1,
+            ServerMessage::Error { .. }
+        ));
+        assert!(matches!(
+            rooms.handle(2, join(1))[0].1,
+            ServerMessage::Joined { .. }
+        ));
+
+        assert!(matches!(
+            rooms.handle(2, shift())[0].1,
+            ServerMessage::ActionFailed { .. }
+        ));
+
+        let broadcast = rooms.handle(1, shift());
+        assert_eq!(broadcast.len(), 2);
+        assert!(broadcast.iter().all(|(_, message)| matches!(
+            message,
+            ServerMessage::State { events, .. }
+                if matches!(events[..], [GameEvent::TilesShifted { .. }])
+        )));
+        for (client, message) in &broadcast {
+            let ServerMessage::State { game, .. } = message else {
+                panic!("Expected the state");
+            };
+            let (own, other) = if *client == 1 { (0, 1) } else { (1, 0) };
+            assert!(game.get_player(own).unwrap().get_to_collect().is_some());
+            assert!(game.get_player(other).unwrap().get_to_collect().is_none());
+        }
+
+        assert!(matches!(
+            rooms.handle(1, shift())[0].1,
+            ServerMessage::ActionFailed { .. }
+        ));
+    }
+
+    #[test]
+    fn rooms_limit_settings() {
+        let mut rooms = Rooms::default();
+        let oversized = [
+            GameStartSettings::new([0, 1].into(), usize::MAX, 3),
+            GameStartSettings::new((0..100).collect(), 7, 6),
+            GameStartSettings::new([0, 1].into(), 7, usize::MAX),
+        ];
+        for settings in oversized {
+            assert!(matches!(
+                rooms.handle(1, ClientMessage::CreateRoom { settings })[..],
+                [(1, ServerMessage::Error { .. })]
+            ));
+        }
+    }
+
+    #[test]
+    fn rooms_time_out_players() {
+        let mut rooms = Rooms::default();
+        rooms.advance_clocks(1000);
+        let time_control = TimeControl::new(Some(1000), None, TimeoutPenalty::SkipMove);
+        let create = ClientMessage::CreateRoom {
+            settings: settings().with_time_control(time_control),
+        };
+        rooms.handle(1, create);
+        rooms.handle(
+            1,
+            ClientMessage::JoinRoom {
+                room_id: 1,
+                player_id: Some(0),
+            },
+        );
+
+        assert!(rooms.advance_clocks(1900).is_empty());
+        let broadcast = rooms.advance_clocks(2000);
+        assert!(matches!(
+            &broadcast[..],
+            [(1, ServerMessage::State { events, .. })]
+                if matches!(events[..], [
+                    GameEvent::TimedOut { player_id: 0 },
+                    GameEvent::TurnChanged { player_id: 1 },
+                ])
+        ));
+        assert!(matches!(
+            rooms.handle(1, shift())[0].1,
+            ServerMessage::ActionFailed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn loopback_client() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(crate::serve(listener));
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}", address))
+            .await
+            .unwrap();
+
+        let create = ClientMessage::CreateRoom {
+            settings: settings(),
+        };
+        let text = serde_json::to_string(&create).unwrap();
+        socket.send(Message::Text(text)).await.unwrap();
+
+        for expected in ["RoomCreated", "Joined", "State"] {
+            let Message::Text(text) = socket.next().await.unwrap().unwrap() else {
+                panic!("Expected a text message");
+            };
+            let message: serde_json::Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(message["type"], expected);
+        }
+    }
+}
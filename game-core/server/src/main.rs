@@ -0,0 +1,15 @@
+use std::env;
+
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:9001".into());
+
+    let listener = TcpListener::bind(&address).await?;
+    println!("Listening on ws://{}", listener.local_addr()?);
+
+    server::serve(listener).await
+}
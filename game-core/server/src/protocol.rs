@@ -0,0 +1,96 @@
+//! The JSON protocol spoken over the WebSocket.
+//!
+//! Every message is a JSON object with a `type` field naming the message and
+//! the remaining fields as its content. Clients first create or join a room,
+//! then send actions for the player they joined as. After every successful
+//! action, the new state of the game is broadcast to everyone in the room,
+//! together with the events describing what changed. Every client only
+//! receives its seat's view of the game: the item cards of the other players
+//! and the items they collect are hidden.
+//!
+//! ```text
+//! -> {"type":"CreateRoom","settings":{"players":[0,1],"width":7,"height":7,"items_per_player":6}}
+//! <- {"type":"RoomCreated","room_id":1}
+//! <- {"type":"State","game":{...}}
+//! -> {"type":"JoinRoom","room_id":1,"player_id":0}
+//! <- {"type":"Joined","room_id":1,"player_id":0}
+//! <- {"type":"State","game":{...}}
+//! -> {"type":"ShiftTiles","side_index":{"side":"Top","index":1}}
+//! <- {"type":"State","game":{...},"events":[{"type":"TilesShifted",...}]}
+//! -> {"type":"MovePlayer","position":{"x":9,"y":9}}
+//! <- {"type":"ActionFailed","failure":{"action":"MovePlayer","kind":{...},"message":"..."}}
+//! ```
+//!
+//! Joining without a `player_id` joins as a spectator, which receives the
+//! state without any item cards but cannot act.
+//!
+//! Games with a time control run on the server's clock, which is advanced
+//! before every message and several times a second. When a player runs out
+//! of time, the new state is broadcast with the `TimedOut` event like after
+//! any other action.
+
+use game::{
+    error::Failure,
+    event::GameEvent,
+    game::GameStartSettings,
+    player::{PlayerId, Position},
+    tile::{Rotation, SideIndex},
+    view::GameView,
+};
+use serde::{Deserialize, Serialize};
+
+pub type RoomId = u64;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ClientMessage {
+    /// Creates a room with a new game. The creator joins it as a spectator.
+    CreateRoom {
+        settings: GameStartSettings,
+    },
+    JoinRoom {
+        room_id: RoomId,
+        #[serde(default)]
+        player_id: Option<PlayerId>,
+    },
+    RotateFreeTile {
+        rotation: Rotation,
+    },
+    ShiftTiles {
+        side_index: SideIndex,
+    },
+    MovePlayer {
+        position: Position,
+    },
+    /// Leaves the game for good. The player is removed from it.
+    Resign,
+    RequestState,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ServerMessage {
+    RoomCreated {
+        room_id: RoomId,
+    },
+    Joined {
+        room_id: RoomId,
+        player_id: Option<PlayerId>,
+    },
+    State {
+        game: Box<GameView>,
+        /// What the last action changed, as far as the recipient may see it.
+        /// Empty if the state was not sent because of an action.
+        #[serde(default)]
+        events: Vec<GameEvent>,
+    },
+    /// The game rejected the action.
+    ActionFailed {
+        failure: Failure,
+    },
+    /// The message could not be handled, e.g. because it was malformed or
+    /// the client is not in a room.
+    Error {
+        message: String,
+    },
+}
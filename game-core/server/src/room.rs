@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use game::{
+    error::{ActionKind, ErrorKind, Failure},
+    event::GameEvent,
+    game::Game,
+    player::PlayerId,
+    replay::Action,
+    view::GameView,
+};
+
+use crate::protocol::{ClientMessage, RoomId, ServerMessage};
+
+pub type ClientId = u64;
+
+/// The largest board side length a room may be created with.
+const MAX_SIDE_LENGTH: usize = 51;
+/// The most players a room may be created with.
+const MAX_PLAYERS: usize = 16;
+/// The most items each player may have to collect in a room.
+const MAX_ITEMS_PER_PLAYER: usize = 100;
+
+/// The messages to send in response to a client message, by recipient.
+pub type Outgoing = Vec<(ClientId, ServerMessage)>;
+
+/// Every room and which client is in which room as which player. This holds
+/// the authoritative state of every game and validates all actions.
+#[derive(Default)]
+pub struct Rooms {
+    rooms: HashMap<RoomId, Room>,
+    clients: HashMap<ClientId, Seat>,
+    next_room_id: RoomId,
+    /// The time of the last [`Rooms::advance_clocks`], in milliseconds.
+    now: u64,
+}
+
+struct Room {
+    game: Game,
+    clients: Vec<ClientId>,
+}
+
+#[derive(Clone, Copy)]
+struct Seat {
+    room_id: RoomId,
+    player_id: Option<PlayerId>,
+}
+
+impl Rooms {
+    pub fn handle(&mut self, client_id: ClientId, message: ClientMessage) -> Outgoing {
+        match message {
+            ClientMessage::CreateRoom { settings }
+                if settings.get_width() > MAX_SIDE_LENGTH
+                    || settings.get_height() > MAX_SIDE_LENGTH
+                    || settings.get_players().len() > MAX_PLAYERS
+                    || settings.get_items_per_player() > MAX_ITEMS_PER_PLAYER =>
+            {
+                error(client_id, "Settings exceed the server's limits")
+            }
+            ClientMessage::CreateRoom { settings } => match Game::new(settings) {
+                Ok(mut game) => {
+                    game.advance_clock(self.now);
+                    self.next_room_id += 1;
+                    let room_id = self.next_room_id;
+                    self.rooms.insert(
+                        room_id,
+                        Room {
+                            game,
+                            clients: Vec::new(),
+                        },
+                    );
+
+                    let mut outgoing = vec![(client_id, ServerMessage::RoomCreated { room_id })];
+                    outgoing.extend(self.join(client_id, room_id, None));
+                    outgoing
+                }
+                Err(err) => vec![(
+                    client_id,
+                    ServerMessage::ActionFailed {
+                        failure: Failure::start_game(err),
+                    },
+                )],
+            },
+            ClientMessage::JoinRoom { room_id, player_id } => {
+                self.join(client_id, room_id, player_id)
+            }
+            ClientMessage::RequestState => match self.clients.get(&client_id) {
+                Some(_) => vec![(client_id, self.state(client_id))],
+                None => error(client_id, "Not in a room"),
+            },
+            ClientMessage::RotateFreeTile { rotation } => {
+                self.act(client_id, |_| Action::RotateFreeTile { rotation })
+            }
+            ClientMessage::ShiftTiles { side_index } => {
+                self.act(client_id, |player_id| Action::ShiftTiles {
+                    player_id,
+                    side_index,
+                })
+            }
+            ClientMessage::MovePlayer { position } => {
+                self.act(client_id, |player_id| Action::MovePlayer {
+                    player_id,
+                    position,
+                })
+            }
+            ClientMessage::Resign => {
+                self.act(client_id, |player_id| Action::RemovePlayer { player_id })
+            }
+        }
+    }
+
+    /// Advances the clocks of every timed game to now, in milliseconds, and
+    /// broadcasts the games in which someone ran out of time. Has to be
+    /// called before handling messages and regularly in between, since
+    /// nothing else makes the time pass.
+    pub fn advance_clocks(&mut self, now: u64) -> Outgoing {
+        self.now = now;
+        let mut outgoing = Vec::new();
+        for room in self.rooms.values_mut() {
+            let events = room.game.advance_clock(now);
+            if !events.is_empty() {
+                outgoing.extend(broadcast(room, &self.clients, &events));
+            }
+        }
+        outgoing
+    }
+
+    /// Forgets the client. Rooms without clients are closed.
+    pub fn disconnect(&mut self, client_id: ClientId) {
+        if let Some(seat) = self.clients.remove(&client_id) {
+            if let Some(room) = self.rooms.get_mut(&seat.room_id) {
+                room.clients.retain(|&c| c != client_id);
+                if room.clients.is_empty() {
+                    self.rooms.remove(&seat.room_id);
+                }
+            }
+        }
+    }
+
+    fn join(
+        &mut self,
+        client_id: ClientId,
+        room_id: RoomId,
+        player_id: Option<PlayerId>,
+    ) -> Outgoing {
+        let Some(room) = self.rooms.get(&room_id) else {
+            return error(client_id, "No such room");
+        };
+
+        if let Some(player_id) = player_id {
+            if room.game.get_players().get(player_id).is_none() {
+                return error(client_id, "No such player in this game");
+            }
+            let taken = self
+                .clients
+                .iter()
+                .filter(|&(&other, _)| other != client_id)
+                .any(|(_, seat)| seat.room_id == room_id && seat.player_id == Some(player_id));
+            if taken {
+                return error(client_id, "Player is already taken");
+            }
+        }
+
+        // Switching seats within the same room must not close the room.
+        let same_room = self
+            .clients
+            .get(&client_id)
+            .is_some_and(|seat| seat.room_id == room_id);
+        if !same_room {
+            self.disconnect(client_id);
+            if let Some(room) = self.rooms.get_mut(&room_id) {
+                room.clients.push(client_id);
+            }
+        }
+        self.clients.insert(client_id, Seat { room_id, player_id });
+
+        vec![
+            (client_id, ServerMessage::Joined { room_id, player_id }),
+            (client_id, self.state(client_id)),
+        ]
+    }
+
+    /// Applies the action of the client's player to the game of its room and
+    /// broadcasts the new state. The game itself rejects shifts and moves out
+    /// of turn, only rotating the free tile has to be checked here.
+    fn act(&mut self, client_id: ClientId, action: impl FnOnce(PlayerId) -> Action) -> Outgoing {
+        let Some(seat) = self.clients.get(&client_id).copied() else {
+            return error(client_id, "Not in a room");
+        };
+        let Some(player_id) = seat.player_id else {
+            return error(client_id, "Spectators cannot act");
+        };
+        let Some(room) = self.rooms.get_mut(&seat.room_id) else {
+            return error(client_id, "No such room");
+        };
+
+        let action = action(player_id);
+        let is_rotation = action.kind() == ActionKind::RotateFreeTile;
+        if is_rotation && room.game.current_player_id() != player_id {
+            let failure = Failure::new(action.kind(), ErrorKind::NotYourTurn { player_id });
+            return vec![(client_id, ServerMessage::ActionFailed { failure })];
+        }
+
+        let mut game = room.game.clone();
+        let events = match action.apply(&mut game) {
+            Ok(events) => events,
+            Err(failure) => return vec![(client_id, ServerMessage::ActionFailed { failure })],
+        };
+        room.game = game;
+
+        broadcast(room, &self.clients, &events)
+    }
+
+    /// The state of the game in the client's room as its seat may see it.
+    fn state(&self, client_id: ClientId) -> ServerMessage {
+        let seat = self.clients[&client_id];
+        ServerMessage::State {
+            game: view(&self.rooms[&seat.room_id].game, seat.player_id),
+            events: Vec::new(),
+        }
+    }
+}
+
+/// Sends everyone in the room their view of the game and the events they may
+/// see.
+fn broadcast(room: &Room, clients: &HashMap<ClientId, Seat>, events: &[GameEvent]) -> Outgoing {
+    room.clients
+        .iter()
+        .map(|&client| {
+            let viewer = clients[&client].player_id;
+            let events = events
+                .iter()
+                .filter(|event| event.is_visible_to(viewer, room.game.get_players()))
+                .cloned()
+                .collect();
+            (
+                client,
+                ServerMessage::State {
+                    game: view(&room.game, viewer),
+                    events,
+                },
+            )
+        })
+        .collect()
+}
+
+fn view(game: &Game, viewer: Option<PlayerId>) -> Box<GameView> {
+    Box::new(match viewer {
+        Some(player_id) => game.view_for(player_id),
+        None => game.spectator_view(),
+    })
+}
+
+fn error(client_id: ClientId, message: &str) -> Outgoing {
+    vec![(
+        client_id,
+        ServerMessage::Error {
+            message: message.into(),
+        },
+    )]
+}
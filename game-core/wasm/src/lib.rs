@@ -1,60 +1,165 @@
 mod result;
 
+use ai::StrategyKind;
 use game::{
-    board::{NewBoardError, ShiftTileError},
-    game::{Game, GameError, GameStartSettings, NewGameError},
-    player::{MoveError, PlayerId, Position},
+    error::{ActionKind, ErrorKind, Failure},
+    event::GameEvent,
+    game::{Game, GameStartSettings},
+    history::History,
+    player::{PlayerId, Position},
+    replay::{Action, Replay},
     tile::{Rotation, SideIndex},
+    view::GameView,
 };
-use std::collections::VecDeque;
-
 use log::Level;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-type ActionResult = result::Result<Game, String>;
+type ActionResult = result::Result<Game, Failure>;
 
 #[wasm_bindgen]
 pub struct GameCore {
-    history: VecDeque<Game>,
+    history: Option<History<Snapshot>>,
+    history_size: usize,
+    keep_branches: bool,
     last_path: Option<Vec<Position>>,
+    /// The events of the last successful action.
+    last_events: Vec<GameEvent>,
+    /// Only present if the game was started from settings.
+    replay: Option<Replay>,
+}
+
+struct Snapshot {
+    game: Game,
+    /// The length of the replay before the actions leading to this game.
+    replay_len: usize,
+    /// The actions leading to this game.
+    actions: Vec<Action>,
+}
+
+impl Snapshot {
+    /// Whether this is the result of one or more actions, all of the kind.
+    fn is_only(&self, kind: ActionKind) -> bool {
+        !self.actions.is_empty() && self.actions.iter().all(|action| action.kind() == kind)
+    }
 }
 
 impl GameCore {
-    fn get_last_error() -> String {
-        "Cannot complete action: Game not started".into()
+    fn get_last_error(action: ActionKind) -> Failure {
+        Failure::new(action, ErrorKind::NotStarted)
+    }
+
+    fn get_history(&self, action: ActionKind) -> Result<&History<Snapshot>, Failure> {
+        self.history
+            .as_ref()
+            .ok_or_else(|| Self::get_last_error(action))
+    }
+
+    fn get_history_mut(&mut self, action: ActionKind) -> Result<&mut History<Snapshot>, Failure> {
+        self.history
+            .as_mut()
+            .ok_or_else(|| Self::get_last_error(action))
     }
 
-    fn get_last(&self) -> Result<&Game, String> {
-        self.history.back().ok_or_else(Self::get_last_error)
+    fn get_last(&self, action: ActionKind) -> Result<&Game, Failure> {
+        self.get_history(action)
+            .map(|history| &history.current().game)
     }
 
-    fn get_last_mut(&mut self) -> Result<&mut Game, String> {
-        self.history.back_mut().ok_or_else(Self::get_last_error)
+    fn replay_len(&self) -> usize {
+        self.replay.as_ref().map_or(0, Replay::len)
     }
 
+    /// Brings the replay in line with the current snapshot after moving
+    /// through the history.
+    fn sync_replay(&mut self) {
+        if let (Some(replay), Some(history)) = (&mut self.replay, &self.history) {
+            let snapshot = history.current();
+            replay.truncate(snapshot.replay_len);
+            snapshot
+                .actions
+                .iter()
+                .for_each(|action| replay.record(*action));
+        }
+    }
+
+    /// Applies the action to a copy of the last game and pushes the result to
+    /// the history. Consecutive rotations of the free tile, and consecutive
+    /// advances of the clock, are merged into a single step, so that one undo
+    /// reverts all of them. Only the last rotation matters, but every advance
+    /// of the clock is kept for the replay, since timeouts depend on all of
+    /// them.
     fn do_action(
         &mut self,
-        action: impl FnOnce(&mut Game) -> Result<(), String>,
-    ) -> Result<Game, String> {
-        self.get_last_mut()
-            .map(|game| game.clone())
-            .and_then(|mut game| {
-                action(&mut game)?;
-                if self.history.len() < self.history.capacity() {
-                    self.history.push_back(game.clone());
-                } else {
-                    self.history.rotate_left(1);
-                    *self.history.back_mut().unwrap() = game.clone();
-                }
-                Ok(game)
-            })
+        kind: ActionKind,
+        actions: &[Action],
+        action: impl FnOnce(&mut Game) -> Result<Vec<GameEvent>, Failure>,
+    ) -> Result<Game, Failure> {
+        self.last_events.clear();
+        let replay_len = self.replay_len();
+        let history = self.get_history_mut(kind)?;
+        let merge = matches!(kind, ActionKind::RotateFreeTile | ActionKind::AdvanceClock)
+            && history.current().is_only(kind)
+            && history.branch_count() == 0;
+
+        let mut game = history.current().game.clone();
+        let events = action(&mut game)?;
+
+        let snapshot = Snapshot {
+            game: game.clone(),
+            replay_len,
+            actions: actions.to_vec(),
+        };
+        if merge {
+            let current = history.current_mut();
+            let actions = match kind {
+                ActionKind::AdvanceClock => [current.actions.as_slice(), actions].concat(),
+                _ => snapshot.actions,
+            };
+            *current = Snapshot {
+                replay_len: current.replay_len,
+                actions,
+                ..snapshot
+            };
+        } else {
+            history.push(snapshot);
+        }
+
+        self.sync_replay();
+        self.last_events = events;
+        Ok(game)
     }
 
     fn do_action_result(
         &mut self,
-        action: impl FnOnce(&mut Game) -> Result<(), String>,
+        kind: ActionKind,
+        actions: &[Action],
+        action: impl FnOnce(&mut Game) -> Result<Vec<GameEvent>, Failure>,
     ) -> ActionResult {
-        self.do_action(action).into()
+        self.do_action(kind, actions, action).into()
+    }
+
+    /// The path of the player moved by the last action, if any.
+    fn moved_path(&self) -> Option<Vec<Position>> {
+        self.last_events.iter().find_map(|event| match event {
+            GameEvent::PlayerMoved { path, .. } => Some(path.clone()),
+            _ => None,
+        })
+    }
+
+    /// Starts a new history from the game, without a replay.
+    fn replace_game(&mut self, game: Game) {
+        let snapshot = Snapshot {
+            game,
+            replay_len: 0,
+            actions: Vec::new(),
+        };
+        self.history = Some(History::new(
+            snapshot,
+            self.history_size,
+            self.keep_branches,
+        ));
+        self.replay = None;
+        self.last_events.clear();
     }
 }
 
@@ -63,138 +168,257 @@ impl GameCore {
     #[wasm_bindgen(constructor)]
     pub fn new(history_size: usize) -> Self {
         Self {
-            history: VecDeque::with_capacity(history_size),
+            history: None,
+            history_size,
+            keep_branches: false,
             last_path: None,
+            last_events: Vec::new(),
+            replay: None,
         }
     }
 
-    pub fn currently_reachable(&self) -> result::Result<Vec<Position>, String> {
-        self.get_last()
+    /// Whether undone states are kept as branches when a new action is taken
+    /// after undoing. Takes effect with the next game.
+    pub fn set_keep_branches(&mut self, keep_branches: bool) {
+        self.keep_branches = keep_branches;
+    }
+
+    pub fn currently_reachable(&self) -> result::Result<Vec<Position>, Failure> {
+        self.get_last(ActionKind::MovePlayer)
             .and_then(|game| {
                 game.currently_reachable()
-                    .ok_or_else(|| "Cannot move player: Game has ended".into())
+                    .ok_or_else(|| Failure::new(ActionKind::MovePlayer, ErrorKind::GameOver))
             })
             .into()
     }
 
-    pub fn set_game(&mut self, game: Game) {
-        self.history.clear();
-        self.history.push_back(game);
+    /// Replaces the current game. Since its origin is unknown, it is
+    /// validated first and no replay is recorded until the next game is
+    /// started.
+    pub fn set_game(&mut self, game: Game) -> ActionResult {
+        if let Err(err) = game.validate() {
+            log::warn!("Rejected game: {}", err);
+            return Err(Failure::set_game(err)).into();
+        }
+        self.replace_game(game.clone());
+        Ok(game).into()
     }
 
     pub fn start_game(&mut self, settings: GameStartSettings) -> ActionResult {
-        Game::new(settings)
-            .map_err(|err| {
-                match err {
-                    NewGameError::PlayerError => "Cannot start game: Not enough players",
-                    NewGameError::BoardError(NewBoardError::TooSmall) => {
-                        "Cannot start game: Side length too small"
-                    }
-                    NewGameError::BoardError(NewBoardError::EvenLength) => {
-                        "Cannot start game: Invalid to side length"
-                    }
-                }
-                .into()
-            })
-            .map(|game| {
-                self.set_game(game.clone());
-                game
+        let replay = Replay::new(settings);
+        replay
+            .initial_game()
+            .inspect(|game| {
+                self.replace_game(game.clone());
+                self.replay = Some(replay.clone());
             })
             .into()
     }
 
     pub fn rotate_free_tile(&mut self, rotation: Rotation) -> ActionResult {
-        self.get_last_mut()
-            .and_then(|game| {
-                if game.rotate_free_tile(rotation) {
-                    Ok(game.clone())
-                } else {
-                    Err("Cannot rotate tile: Game has ended".into())
-                }
-            })
-            .into()
+        let actions = [Action::RotateFreeTile { rotation }];
+        self.do_action_result(ActionKind::RotateFreeTile, &actions, |game| {
+            if game.rotate_free_tile(rotation) {
+                Ok(Vec::new())
+            } else {
+                Err(Failure::new(
+                    ActionKind::RotateFreeTile,
+                    ErrorKind::GameOver,
+                ))
+            }
+        })
     }
 
-    pub fn shift_tiles(&mut self, side_index: SideIndex) -> ActionResult {
-        self.do_action_result(|game| {
-            game.shift_tiles(side_index).map_err(|err| {
-                match err {
-                    GameError::GameOver => "Cannot shift tiles: Game has ended",
-                    GameError::StateError => {
-                        "Cannot shift tiles: Player has to end turn by moving figure"
-                    }
-                    GameError::ActionError(ShiftTileError::OutOfBounds) => {
-                        "Cannot shift tiles: No such row/column exists"
-                    }
-                    GameError::ActionError(ShiftTileError::UnMovable) => {
-                        "Cannot shift tiles: Specified row/column is not movable"
-                    }
-                    GameError::ActionError(ShiftTileError::UndoMove) => {
-                        "Cannot shift tiles: Tile cannot be pushed back in where it was previously pushed out"
-                    }
-                }
-                .into()
-            })
+    pub fn shift_tiles(&mut self, player_id: PlayerId, side_index: SideIndex) -> ActionResult {
+        let actions = [Action::ShiftTiles {
+            player_id,
+            side_index,
+        }];
+        self.do_action_result(ActionKind::ShiftTiles, &actions, |game| {
+            game.shift_tiles(player_id, side_index)
+                .map_err(|err| Failure::shift_tiles(err, player_id, side_index))
         })
     }
 
     pub fn remove_player(&mut self, player_id: PlayerId) -> ActionResult {
-        self.do_action_result(|game| {
-            game.remove_player(player_id).map_err(|err| {
-                match err {
-                    GameError::GameOver => "Cannot remove player: Game has ended",
-                    _ => "Cannot remove player: No such player exists",
-                }
-                .into()
-            })
+        let actions = [Action::RemovePlayer { player_id }];
+        self.do_action_result(ActionKind::RemovePlayer, &actions, |game| {
+            game.remove_player(player_id)
+                .map_err(|err| Failure::remove_player(err, player_id))
         })
     }
 
     pub fn move_player(&mut self, player_id: PlayerId, position: Position) -> ActionResult {
-        let mut path = None;
-        let res = self.do_action_result(|game| {
+        let actions = [Action::MovePlayer {
+            player_id,
+            position,
+        }];
+        let res = self.do_action_result(ActionKind::MovePlayer, &actions, |game| {
             game.move_player(player_id, position)
-                .map_err(|err| {
-                    match err {
-                        GameError::GameOver => "Cannot move player: Game has ended",
-                        GameError::StateError => {
-                            "Cannot move player: Player has to shift tiles first"
-                        }
-                        GameError::ActionError(MoveError::InvalidPosition) => {
-                            "Cannot move player: Position is not on the board"
-                        }
-                        GameError::ActionError(MoveError::InvalidPlayer) => {
-                            "Cannot move player: No such player exists"
-                        }
-                        GameError::ActionError(MoveError::UnreachablePosition) => {
-                            "Cannot move player: Position is not reachable by player"
-                        }
-                    }
-                    .into()
-                })
-                .map(|p| path = Some(p))
+                .map_err(|err| Failure::move_player(err, player_id, position))
         });
 
-        self.last_path = path;
+        self.last_path = self.moved_path();
 
         res
     }
 
-    pub fn last_path(&mut self) -> result::Result<Vec<Position>, String> {
+    /// Advances the clock of a timed game to the timestamp in milliseconds,
+    /// e.g. `Date.now()`. It should be advanced before every action, and
+    /// regularly while waiting for a player, so that timeouts are noticed.
+    pub fn advance_clock(&mut self, now: u64) -> ActionResult {
+        let actions = [Action::AdvanceClock { now }];
+        self.do_action_result(ActionKind::AdvanceClock, &actions, |game| {
+            Ok(game.advance_clock(now))
+        })
+    }
+
+    /// Plays the whole turn of the current player with the given strategy.
+    pub fn play_computer_turn(&mut self, strategy: StrategyKind) -> ActionResult {
+        let action = ActionKind::PlayComputerTurn;
+        let turn = match self.get_last(action) {
+            Ok(game) => strategy.create().choose_turn(game),
+            Err(err) => return Err(err).into(),
+        };
+
+        let Some(turn) = turn else {
+            return Err(Failure::new(action, ErrorKind::NoLegalTurn)).into();
+        };
+
+        let player_id = match self.get_last(action) {
+            Ok(game) => game.current_player_id(),
+            Err(err) => return Err(err).into(),
+        };
+        let actions = [
+            Action::RotateFreeTile {
+                rotation: turn.get_rotation(),
+            },
+            Action::ShiftTiles {
+                player_id,
+                side_index: turn.get_side_index(),
+            },
+            Action::MovePlayer {
+                player_id,
+                position: turn.get_destination(),
+            },
+        ];
+
+        let res = self.do_action_result(action, &actions, |game| {
+            game.rotate_free_tile(turn.get_rotation());
+            let mut events = game
+                .shift_tiles(player_id, turn.get_side_index())
+                .map_err(|err| Failure::shift_tiles(err, player_id, turn.get_side_index()))?;
+            events.extend(
+                game.move_player(player_id, turn.get_destination())
+                    .map_err(|err| Failure::move_player(err, player_id, turn.get_destination()))?,
+            );
+            Ok(events)
+        });
+
+        self.last_path = self.moved_path();
+
+        res
+    }
+
+    pub fn last_path(&mut self) -> result::Result<Vec<Position>, Failure> {
         self.last_path
             .take()
-            .ok_or_else(|| "Player has not moved yet".into())
+            .ok_or_else(|| Failure::new(ActionKind::GetLastPath, ErrorKind::NotMovedYet))
+            .into()
+    }
+
+    /// What changed with the last action. Empty if it failed, and after
+    /// moving through the history or replacing the game.
+    pub fn last_events(&self) -> result::Result<Vec<GameEvent>, Failure> {
+        self.get_history(ActionKind::GetLastEvents)
+            .map(|_| self.last_events.clone())
+            .into()
+    }
+
+    /// What the player, or a spectator if `None`, may see of the current
+    /// game. Hosts send this instead of the game to remote participants.
+    pub fn view_for(&self, player_id: Option<PlayerId>) -> result::Result<GameView, Failure> {
+        self.get_last(ActionKind::GetView)
+            .map(|game| match player_id {
+                Some(player_id) => game.view_for(player_id),
+                None => game.spectator_view(),
+            })
             .into()
     }
 
     pub fn undo_move(&mut self) -> ActionResult {
-        if self.history.len() > 1 {
-            self.history.pop_back();
-            Ok(self.history.back().cloned().unwrap())
-        } else {
-            Err("Cannot undo move: Last state in history".into())
-        }
-        .into()
+        let action = ActionKind::UndoMove;
+        self.last_events.clear();
+        let res = self.get_history_mut(action).and_then(|history| {
+            history
+                .undo()
+                .map(|snapshot| snapshot.game.clone())
+                .ok_or_else(|| Failure::new(action, ErrorKind::NoHistory))
+        });
+        self.sync_replay();
+        res.into()
+    }
+
+    pub fn redo_move(&mut self) -> ActionResult {
+        let action = ActionKind::RedoMove;
+        self.last_events.clear();
+        let res = self.get_history_mut(action).and_then(|history| {
+            history
+                .redo()
+                .map(|snapshot| snapshot.game.clone())
+                .ok_or_else(|| Failure::new(action, ErrorKind::NoRedo))
+        });
+        self.sync_replay();
+        res.into()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.as_ref().is_some_and(History::can_undo)
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.as_ref().is_some_and(History::can_redo)
+    }
+
+    /// The number of branches that can be redone from the current game. Only
+    /// more than one if branches are kept.
+    pub fn branch_count(&self) -> usize {
+        self.history.as_ref().map_or(0, History::branch_count)
+    }
+
+    /// Redoes the given branch, in the order the branches were created.
+    pub fn redo_branch(&mut self, branch: usize) -> ActionResult {
+        let action = ActionKind::RedoMove;
+        self.last_events.clear();
+        let res = self.get_history_mut(action).and_then(|history| {
+            history
+                .redo_branch(branch)
+                .map(|snapshot| snapshot.game.clone())
+                .ok_or_else(|| Failure::new(action, ErrorKind::NoRedo))
+        });
+        self.sync_replay();
+        res.into()
+    }
+
+    /// The settings and every action of the current game so far.
+    pub fn get_replay(&self) -> result::Result<Replay, Failure> {
+        self.replay
+            .clone()
+            .ok_or_else(|| Failure::new(ActionKind::GetReplay, ErrorKind::NoReplay))
+            .into()
+    }
+
+    /// Continues the game of the replay after its first `action_count`
+    /// actions, discarding the ones after.
+    pub fn load_replay(&mut self, mut replay: Replay, action_count: usize) -> ActionResult {
+        replay
+            .game_at(action_count)
+            .inspect(|game| {
+                replay.truncate(action_count);
+                self.replace_game(game.clone());
+                self.replay = Some(replay);
+            })
+            .into()
     }
 }
 
@@ -19,8 +19,8 @@ export function useTilesMap(board: Ref<Board | null>) {
         tile.id,
         {
           tile,
-          x: index % b.side_length,
-          y: Math.floor(index / b.side_length),
+          x: index % b.width,
+          y: Math.floor(index / b.width),
         },
       ])
     );
@@ -50,7 +50,7 @@ export function useTilesMap(board: Ref<Board | null>) {
       if (freeTile.side_with_index !== undefined) {
         const { x, y } = sideIndexToXY(
           oppositeSideIndex(freeTile.side_with_index),
-          b.side_length
+          b
         );
         const insertedTile = [...tiles.value.values()].find(
           (tile) => tile.x === x && tile.y === y
@@ -82,7 +82,7 @@ export function useTilesMap(board: Ref<Board | null>) {
       // Animate the removed tile going beyond the edge
       if (freeTile.side_with_index !== undefined) {
         const { x, y } = stepTowards(
-          sideIndexToXY(freeTile.side_with_index, b.side_length),
+          sideIndexToXY(freeTile.side_with_index, b),
           freeTile.side_with_index.side
         );
 
@@ -109,9 +109,10 @@ export function useTilesMap(board: Ref<Board | null>) {
     if (board.value === null || tile === null) {
       return {};
     }
+    const scale = tilesPerSide(board.value);
     return {
-      top: (tile.y / board.value.side_length) * 100 + "%",
-      left: (tile.x / board.value.side_length) * 100 + "%",
+      top: (tile.y / scale) * 100 + "%",
+      left: (tile.x / scale) * 100 + "%",
     };
   }
 
@@ -122,17 +123,25 @@ export function useTilesMap(board: Ref<Board | null>) {
   };
 }
 
-function sideIndexToXY(sideIndex: SideIndex, side_length: number) {
+/**
+ * The number of tiles that fit along each side of the square the board is
+ * drawn in. Rectangular boards leave the rest of the square empty.
+ */
+export function tilesPerSide(board: Board) {
+  return Math.max(board.width, board.height);
+}
+
+function sideIndexToXY(sideIndex: SideIndex, board: Board) {
   let x: number, y: number;
   if (sideIndex.side === "Top") {
     x = sideIndex.index;
     y = 0;
   } else if (sideIndex.side === "Right") {
-    x = side_length - 1;
+    x = board.width - 1;
     y = sideIndex.index;
   } else if (sideIndex.side === "Bottom") {
     x = sideIndex.index;
-    y = side_length - 1;
+    y = board.height - 1;
   } else if (sideIndex.side === "Left") {
     x = 0;
     y = sideIndex.index;
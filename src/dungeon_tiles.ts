@@ -56,23 +56,31 @@ function exactTile(
   };
 }
 
-function assembleTile(
-  pieces: ({
-    x: number;
-    y: number;
-    rotation?: 0 | 90 | 180 | 270;
-  } | null)[]
-) {
+type Piece = {
+  x: number;
+  y: number;
+  rotation?: 0 | 90 | 180 | 270;
+  /** Only draws the given rows of the cell. */
+  rows?: { from: number; to: number };
+};
+
+function assembleTile(pieces: (Piece | Piece[] | null)[]) {
   if (pieces.length !== 9) {
     throw new Error("Invalid number of pieces");
   }
   const ctx = makeCtx(16 * 3, 16 * 3);
-  pieces.forEach((piece, i) => {
-    if (piece) {
+  pieces.forEach((cell, i) => {
+    const layers = cell === null ? [] : Array.isArray(cell) ? cell : [cell];
+    for (const piece of layers) {
       ctx.save();
       const x = (i % 3) * 16;
       const y = Math.floor(i / 3) * 16;
       ctx.translate(x, y);
+      if (piece.rows) {
+        ctx.beginPath();
+        ctx.rect(0, piece.rows.from, 16, piece.rows.to - piece.rows.from);
+        ctx.clip();
+      }
       if (piece.rotation) {
         ctx.translate(8, 8);
         ctx.rotate((piece.rotation * Math.PI) / 180);
@@ -117,6 +125,11 @@ function makeAbyssTiles() {
     x: 13,
     y: 15,
   };
+  // The open half of the T-junction twice, so all four sides are open.
+  let crossing = [
+    { ...tJunction, rotation: 180 as const, rows: { from: 0, to: 8 } },
+    { ...tJunction, rows: { from: 8, to: 16 } },
+  ];
 
   return {
     LShape: assembleTile([
@@ -170,5 +183,57 @@ function makeAbyssTiles() {
       },
       null,
     ]),
+    Cross: assembleTile([
+      null,
+      {
+        ...straight,
+        rotation: 270,
+      },
+      null,
+      straight,
+      crossing,
+      straight,
+      null,
+      {
+        ...straight,
+        rotation: 90,
+      },
+      null,
+    ]),
+    DeadEnd: assembleTile([
+      null,
+      {
+        ...straight,
+        rotation: 270,
+      },
+      null,
+      null,
+      cliff,
+      null,
+      null,
+      null,
+      null,
+    ]),
+    // The vertical passage runs over the horizontal one.
+    Bridge: assembleTile([
+      null,
+      {
+        ...straight,
+        rotation: 270,
+      },
+      null,
+      straight,
+      {
+        ...straight,
+        rotation: 90,
+      },
+      straight,
+      null,
+      {
+        ...straight,
+        rotation: 90,
+      },
+      null,
+    ]),
   };
 }
@@ -10,6 +10,7 @@ import init, {
   type Result,
   type Position,
   type Player,
+  type Failure,
 } from "../game-core/pkg";
 import { useLocalStorage } from "@/local-storage";
 
@@ -21,7 +22,8 @@ export type GameStartSettings = Omit<G, "players"> & { players: Set<PlayerId> };
 export function DefaultGameStartSettings(): GameStartSettings {
   return {
     players: new Set<PlayerId>(),
-    side_length: 7,
+    width: 7,
+    height: 7,
     items_per_player: 6,
   };
 }
@@ -53,22 +55,22 @@ export function useGame(errorHandler: (error: string) => void) {
       if (game === null) return;
       if (game.phase !== "MovePlayer") return;
 
-      const v: Result<Position[], string> = core.currently_reachable();
+      const v: Result<Position[], Failure> = core.currently_reachable();
       if (v.type === "Ok") {
         reachable.value = new Set(v.value.map(positionToString));
       } else {
-        errorHandler(v.value);
+        errorHandler(v.value.message);
       }
     },
     { immediate: true, deep: true }
   );
 
-  function handleResult(result: Result<Game, string>) {
+  function handleResult(result: Result<Game, Failure>) {
     if (result.type === "Ok") {
       game.value = result.value;
       saveState();
     } else {
-      errorHandler(result.value);
+      errorHandler(result.value.message);
     }
   }
 
@@ -89,8 +91,10 @@ export function useGame(errorHandler: (error: string) => void) {
   }
 
   let lastInterval: number | null = null;
-  function shiftTiles(side_index: SideIndex) {
-    handleResult(core.shift_tiles(side_index));
+  function shiftTiles(side_index: SideIndex, id?: PlayerId) {
+    const player = id ?? game.value?.players.player_turn;
+    if (player === undefined) return;
+    handleResult(core.shift_tiles(player, side_index));
     if (lastInterval !== null) {
       clearInterval(lastInterval);
     }
@@ -102,7 +106,7 @@ export function useGame(errorHandler: (error: string) => void) {
 
   function movePlayer(id: PlayerId, x: number, y: number) {
     handleResult(core.move_player(id, { x, y }));
-    let result: Result<Position[], string> = core.last_path();
+    let result: Result<Position[], Failure> = core.last_path();
 
     if (result.type === "Ok") {
       const path = result.value;
@@ -129,8 +133,7 @@ export function useGame(errorHandler: (error: string) => void) {
   }
 
   function setGame(g: Game) {
-    game.value = g;
-    core.set_game(g);
+    handleResult(core.set_game(g));
   }
 
   function saveState() {
@@ -82,13 +82,9 @@ export function useServer(id: Ref<string>, game: ServerGame) {
             game.rotateFreeTile();
           }
         } else if (data.name === "shiftTiles") {
-          if (game.activePlayer.value === playerId) {
-            game.shiftTiles(data.side_index);
-          }
+          game.shiftTiles(data.side_index, playerId);
         } else if (data.name === "movePlayer") {
-          if (game.activePlayer.value === playerId) {
-            game.movePlayer(playerId, data.x, data.y);
-          }
+          game.movePlayer(playerId, data.x, data.y);
         } else if (data.name === "requestGame") {
           updateGame(game.game.value);
         } else {